@@ -1,7 +1,6 @@
 use std::time::Duration;
 
 use macroquad::prelude::*;
-use macroquad::window;
 use miniquad::conf::Platform;
 
 // --- --- --- --- --- --- --- --- --- --- //
@@ -10,37 +9,65 @@ use miniquad::conf::Platform;
 mod mq_ui;
 use mq_ui::*;
 
-#[derive(Debug)]
-struct FpsCounter<'a> {
-	time_since_last_update: f32,
-	display: String,
-	font: Option<&'a Font>
-}
-impl<'a> FpsCounter<'a> {
-	pub fn new(font: Option<&'a Font>) -> Self {
-		FpsCounter {
-			time_since_last_update: 0.0,
-			display: "FPS: ".to_owned(),
-			font: font
+fn gen_search_icon() -> Texture2D {
+	// draw a magnifying glass into a small image
+	let mut img = Image::gen_image_color(32, 32, BLANK);
+	for y in 0..32 {
+		for x in 0..32 {
+			let (dx, dy) = (x as f32 - 13.0, y as f32 - 13.0);
+			let dist = (dx * dx + dy * dy).sqrt();
+			let on_ring = dist > 7.0 && dist < 10.0;
+			let on_handle = x > 19 && y > 19 && (x as i32 - y as i32).abs() < 3;
+			if on_ring || on_handle {
+				img.set_pixel(x, y, WHITE);
+			}
 		}
 	}
-	pub fn update(&mut self) {
-		// update display
-		self.time_since_last_update += get_frame_time();
-		if self.time_since_last_update > 0.5 {
-			self.time_since_last_update = 0.0;
-			let fps = get_fps();
-			self.display = "FPS: ".to_owned() + &fps.to_string();
-		}
-		let y = window::screen_height() - 5.0;
-		// render display
-		draw_text_ex(&self.display, 5.0, y, TextParams {
-			font: self.font,
-			font_size: 18,
-			color: GREEN,
+	let texture = Texture2D::from_image(&img);
+	texture.set_filter(FilterMode::Linear);
+	texture
+}
+
+fn build_loading_ui() -> UiRoot {
+	UiRoot::new().with(|root| {
+		let panel = UiBox::new(1, UiBoxParams {
+			pos_size: UiRect::from_px(0.0, 0.0, 300.0, 110.0),
+			alignment: UiAlign::FullCenter,
+			theme: Some(&root.theme),
 			..Default::default()
+		}).with(|panel| {
+			panel.add_child(UiElement::Spinner(UiSpinner::new(2, UiSpinnerParams {
+				pos_size: UiRect::from_px(16.0, 16.0, 32.0, 32.0),
+				theme: Some(&root.theme),
+				..Default::default()
+			})));
+			panel.add_child(UiElement::Text(UiText::new(3, UiTextParams {
+				text: "Loading".to_owned(),
+				pos_size: UiRect::from_px(60.0, 20.0, 220.0, 24.0),
+				..Default::default()
+			})));
+			panel.add_child(UiElement::Progress(UiProgress::new(4, UiProgressParams {
+				pos_size: UiRect::from_px(16.0, 66.0, 268.0, 24.0),
+				theme: Some(&root.theme),
+				..Default::default()
+			})));
 		});
+		root.add_child(UiElement::Box(panel));
+	})
+}
+
+async fn show_loading(ui: &mut UiRoot, label: &str, progress: f32) {
+	// asset loads block, so draw a frame before each one
+	if let Some(UiElement::Text(e)) = ui.find_element_content_mut(3) {
+		e.text = format!("Loading {}", label);
+	}
+	if let Some(UiElement::Progress(e)) = ui.find_element_content_mut(4) {
+		e.set_value(progress);
 	}
+	ui.update();
+	clear_background(DARKGRAY);
+	ui.render();
+	next_frame().await
 }
 
 // --- --- --- --- --- --- --- --- --- --- //
@@ -65,62 +92,270 @@ fn window_conf() -> Conf {
 async fn main() {
 	// load assets
 	set_pc_assets_folder("assets");
+	let mut loading = build_loading_ui();
+	show_loading(&mut loading, "Helvetica.ttf", 0.0).await;
 	let font = load_ttf_font("Helvetica.ttf").await.unwrap();
+	show_loading(&mut loading, "Helvetica-Bold.ttf", 0.5).await;
+	let bold_font = load_ttf_font("Helvetica-Bold.ttf").await.ok();
+	show_loading(&mut loading, "interface", 1.0).await;
 
 	// states
-	let mut fps_counter = FpsCounter::new(Some(&font));
-	let mut ui = UiRoot::new().with(|root| {
-		// add theme
-		root.theme = UiTheme {
-			font: Some(font.clone()),
-			font_size: 18,
+	let mut theme = UiTheme {
+		font: Some(font.clone()),
+		bold_font,
+		font_size: 18,
+		..Default::default()
+	};
+	let search_icon = theme.add_texture(gen_search_icon());
+	let mut ui = UiRoot::new().with_theme(theme).with(|root| {
+		// perf overlay, toggled with F3
+		root.set_perf_overlay(UiPerfOverlay::new(UiPerfOverlayParams {
+			theme: Some(&root.theme),
 			..Default::default()
-		};
+		}));
 
-		// dialog box
-		let dialog = UiBox::new(4, UiBoxParams {
-			pos_size: UiRect::from_px(100.0, 100.0, 300.0, 25.0),
-			alignment: UiAlign::BottomRight,
+		// circle panel
+		let circle_panel = UiBox::new(18, UiBoxParams {
+			pos_size: UiRect::from_px(0.0, 0.0, 240.0, 240.0),
+			alignment: UiAlign::FullCenter,
 			draggable: true,
-			show_hover: true,
+			raise_on_click: true,
+			drag: UiDrag {
+				bounds: UiDragBounds::Screen,
+				snap: 10.0,
+				..Default::default()
+			},
+			// the border around the canvas is the drag handle
+			padding: UiEdges::all(12.0),
+			theme: Some(&root.theme),
+			..Default::default()
+		}).with(|panel| {
+			let circle = UiCanvas::new(19, UiCanvasParams {
+				draw: Some(UiDrawFn::new(|bounds, _theme| {
+					let (x, y) = (bounds.x + bounds.w / 2.0, bounds.y + bounds.h / 2.0);
+					let r = bounds.w.min(bounds.h) / 2.0 - 2.0;
+					draw_poly(x + 3.0, y + 2.0, 64, r + 6.0, 0.0, BLACK);
+					draw_poly(x, y, 64, r, 0.0, RED);
+				})),
+				..Default::default()
+			});
+			panel.add_child(UiElement::Canvas(circle));
+		});
+		root.add_child(UiElement::Box(circle_panel));
+
+		// dialog window
+		let dialog = UiWindow::new(4, UiWindowParams {
+			pos_size: UiRect::from_px(400.0, 275.0, 300.0, 225.0),
+			alignment: UiAlign::BottomRight,
+			title: "Dialog".to_owned(),
 			theme: Some(&root.theme),
 			..Default::default()
 		}).with(|dialog| {
-			// modify colors directly
-			dialog.color = root.theme.secondary[2];
-			dialog.hover_color = root.theme.secondary[3];
-
-			let dialog_body = UiBox::new(5, UiBoxParams {
-				pos_size: UiRect::from_px(0.0, 25.0, 300.0, 200.0),
-				draggable: false,
-				show_hover: false,
+			let dialog_txt = UiText::new(6, UiTextParams {
+				text: format!("Drag the [b]title bar[/b] [icon={}] [url=help]help[/url]", search_icon.0),
+				pos_size: UiRect::from_px(10.0, 10.0, 10.0, 10.0),
+				markup: true,
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			let dialog_btn = UiButton::new(7, UiButtonParams {
+				pos_size: UiRect::from_px(10.0, 160.0, 100.0, 30.0),
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			dialog.add_child(UiElement::Text(dialog_txt));
+			dialog.add_child(UiElement::Button(dialog_btn));
+
+			let radio = UiRadio::new(8, UiRadioParams {
+				pos_size: UiRect::from_px(10.0, 120.0, 30.0, 30.0),
+				label: "Check this out".to_owned(),
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			dialog.add_child(UiElement::Radio(radio));
+
+			let toggle = UiToggle::new(9, UiToggleParams {
+				pos_size: UiRect::from_px(10.0, 80.0, 150.0, 24.0),
+				label: "Sound effects".to_owned(),
+				checked: true,
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			dialog.add_child(UiElement::Toggle(toggle));
+
+			let image = UiImage::new(10, UiImageParams {
+				pos_size: UiRect::from_px(210.0, 10.0, 80.0, 80.0),
+				texture: Some(search_icon),
+				tint: root.theme.secondary[3],
+				..Default::default()
+			});
+			dialog.add_child(UiElement::Image(image));
+
+			let spawn_count = UiNumberInput::new(11, UiNumberInputParams {
+				pos_size: UiRect::from_px(10.0, 40.0, 150.0, 28.0),
+				value: 10.0,
+				min: 0.0,
+				max: 100.0,
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			dialog.add_child(UiElement::NumberInput(Box::new(spawn_count)));
+		});
+		root.add_child(UiElement::Window(dialog));
+
+		// background color editor
+		let bg_editor = UiWindow::new(12, UiWindowParams {
+			pos_size: UiRect::from_px(520.0, 80.0, 240.0, 325.0),
+			title: "Background".to_owned(),
+			resizable: false,
+			theme: Some(&root.theme),
+			..Default::default()
+		}).with(|editor| {
+			let picker = UiColorPicker::new(13, UiColorPickerParams {
+				pos_size: UiRect::from_px(10.0, 10.0, 220.0, 280.0),
+				color: root.theme.accent[0],
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			editor.add_child(UiElement::ColorPicker(picker));
+		});
+		root.add_child(UiElement::Window(bg_editor));
+
+		// split view
+		let split_window = UiWindow::new(14, UiWindowParams {
+			pos_size: UiRect::from_px(430.0, 420.0, 340.0, 160.0),
+			title: "Split view".to_owned(),
+			theme: Some(&root.theme),
+			..Default::default()
+		}).with(|window| {
+			let split = UiSplit::new(15, UiSplitParams {
+				ratio: 0.4,
+				min_sizes: (60.0, 80.0),
 				theme: Some(&root.theme),
 				..Default::default()
-			}).with(|body| {
-				let dialog_txt = UiText::new(6, UiTextParams {
-					text: "Drag me".to_owned(),
-					pos_size: UiRect::from_px(10.0, 10.0, 10.0, 10.0),
+			}).with(|left, right| {
+				left.add_child(UiElement::Text(UiText::new(16, UiTextParams {
+					text: "Drag the divider to resize the panes, or double click it to collapse this one.".to_owned(),
+					pos_size: UiRect::from_px(8.0, 8.0, 110.0, 110.0),
+					text_align: UiTextAlign::Justify,
+					wrap: true,
+					ellipsis: true,
 					..Default::default()
-				});
-				let dialog_btn = UiButton::new(7, UiButtonParams {
-					pos_size: UiRect::from_px(10.0, 160.0, 100.0, 30.0),
+				})));
+				let frame_plot = UiPlot::new(20, UiPlotParams {
+					pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+					title: "Frame time".to_owned(),
+					y_min: Some(0.0),
 					theme: Some(&root.theme),
 					..Default::default()
-				});
-				body.add_child(UiElement::Text(dialog_txt));
-				body.add_child(UiElement::Button(dialog_btn));
+				}).with_series("ms", UiPlotStyle::Bar);
+				right.add_child(UiElement::Plot(frame_plot));
+			});
+			window.add_child(UiElement::Split(Box::new(split)));
+		});
+		root.add_child(UiElement::Window(split_window));
 
-				let radio = UiRadio::new(8, UiRadioParams {
-					pos_size: UiRect::from_px(10.0, 120.0, 30.0, 30.0),
-					label: "Check this out".to_owned(),
+		// accordion of collapsible sections
+		let properties = UiWindow::new(22, UiWindowParams {
+			pos_size: UiRect::from_px(20.0, 70.0, 220.0, 260.0),
+			title: "Properties".to_owned(),
+			theme: Some(&root.theme),
+			..Default::default()
+		}).with(|window| {
+			let mut sections = UiBox::new(23, UiBoxParams {
+				pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+				clip: true,
+				padding: UiEdges::all(6.0),
+				layout: UiLayout::Column,
+				gap: 4.0,
+				cross_align: UiCrossAlign::Stretch,
+				theme: Some(&root.theme),
+				..Default::default()
+			});
+			let topics = [
+				("General", "Sections share a group, so opening one closes the others."),
+				("Layout", "Siblings below a section move up and down as it animates."),
+				("About", "Click a header to expand or collapse its content."),
+			];
+			for (i, (title, body)) in topics.iter().enumerate() {
+				let id = 24 + i as u32 * 2;
+				let section = UiCollapsible::new(id, UiCollapsibleParams {
+					pos_size: UiRect::from_px(0.0, 0.0, 200.0, 100.0),
+					title: title.to_string(),
+					expanded: i == 0,
+					group: Some(1),
 					theme: Some(&root.theme),
 					..Default::default()
+				}).with(|content| {
+					content.add_child(UiElement::Text(UiText::new(id + 1, UiTextParams {
+						text: body.to_string(),
+						pos_size: UiRect::from_px(8.0, 6.0, 184.0, 60.0),
+						wrap: true,
+						..Default::default()
+					})));
 				});
-				body.add_child(UiElement::Radio(radio));
+				sections.add_child(UiElement::Collapsible(Box::new(section)));
+			}
+			window.add_child(UiElement::Box(sections));
+		});
+		root.add_child(UiElement::Window(properties));
+
+		// settings form on a grid
+		let settings = UiWindow::new(30, UiWindowParams {
+			pos_size: UiRect::from_px(250.0, 330.0, 280.0, 200.0),
+			title: "Settings".to_owned(),
+			theme: Some(&root.theme),
+			..Default::default()
+		}).with(|window| {
+			let form = UiBox::new(31, UiBoxParams {
+				pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+				padding: UiEdges::all(10.0),
+				layout: UiLayout::Grid,
+				columns: vec![UiTrack::Auto, UiTrack::Fr(1.0)],
+				rows: vec![UiTrack::Auto, UiTrack::Auto, UiTrack::Auto, UiTrack::Px(34.0)],
+				gap: 8.0,
+				cross_align: UiCrossAlign::Stretch,
+				theme: Some(&root.theme),
+				..Default::default()
+			}).with(|form| {
+				let label = |id: u32, text: &str| UiElement::Text(UiText::new(id, UiTextParams {
+					text: text.to_owned(),
+					pos_size: UiRect {
+						w: UiSize::Auto,
+						h: UiSize::Px(24.0),
+						..Default::default()
+					},
+					vertical_align: UiTextVAlign::Middle,
+					..Default::default()
+				}));
+				form.add_child(label(32, "Name"));
+				form.add_child(UiElement::Input(UiInput::new(33, UiInputParams {
+					placeholder: "Player".to_owned(),
+					theme: Some(&root.theme),
+					..Default::default()
+				})));
+				form.add_child(label(34, "Volume"));
+				form.add_child(UiElement::NumberInput(Box::new(UiNumberInput::new(35, UiNumberInputParams {
+					value: 80.0,
+					max: 100.0,
+					step: 5.0,
+					theme: Some(&root.theme),
+					..Default::default()
+				}))));
+				form.add_child(label(36, "Fullscreen"));
+				form.add_child(UiElement::Toggle(UiToggle::new(37, UiToggleParams {
+					theme: Some(&root.theme),
+					..Default::default()
+				})));
+				form.add_grid_child(UiElement::Button(UiButton::new(38, UiButtonParams {
+					text: "Apply".to_owned(),
+					theme: Some(&root.theme),
+					..Default::default()
+				})), UiGridCell::at(0, 3).span(2, 1));
 			});
-			dialog.add_child(UiElement::Box(dialog_body));
+			window.add_child(UiElement::Box(form));
 		});
-		root.add_child(UiElement::Box(dialog));
+		root.add_child(UiElement::Window(settings));
 	});
 
 	// nav bar
@@ -130,39 +365,114 @@ async fn main() {
 			y: UiSize::Px(0.0),
 			w: UiSize::Percent(1.0),
 			h: UiSize::Px(50.0),
+			..Default::default()
 		},
+		layout: UiLayout::Row,
+		gap: 20.0,
+		justify: UiJustify::Center,
+		cross_align: UiCrossAlign::Center,
 		theme: Some(&ui.theme),
 		..Default::default()
 	});
 	let search_input = UiInput::new(2, UiInputParams {
-		pos_size: UiRect::from_px(170.0, 10.0, 320.0, 30.0),
-		alignment: UiAlign::TopCenter,
+		pos_size: UiRect {
+			w: UiSize::Fill,
+			h: UiSize::Px(30.0),
+			..Default::default()
+		}.with_min_size(120.0, 30.0).with_max_size(320.0, 30.0),
 		placeholder: "Search".to_owned(),
 		theme: Some(&ui.theme),
+		..Default::default()
 	});
 	let search_btn = UiButton::new(3, UiButtonParams {
-		pos_size: UiRect::from_px(510.0, 10.0, 100.0, 30.0),
+		pos_size: UiRect {
+			w: UiSize::Auto,
+			h: UiSize::Px(30.0),
+			..Default::default()
+		},
 		text: "Search".to_owned(),
-		alignment: UiAlign::TopCenter,
+		icon: Some(search_icon),
 		theme: Some(&ui.theme),
 		..Default::default()
 	});
-	nav.add_child(UiElement::Input(search_input));
-	nav.add_child(UiElement::Button(search_btn));
+	nav.add_flex_child(UiElement::Input(search_input), UiFlex::default());
+	nav.add_flex_child(UiElement::Button(search_btn), UiFlex::fixed());
 	ui.add_child(UiElement::Box(nav));
 
-	let bg_color = ui.theme.accent[0];
+	// developer console, toggled with `
+	let mut console = UiConsole::new(21, UiConsoleParams {
+		theme: Some(&ui.theme),
+		..Default::default()
+	}).with_command("echo", |args| {
+		Ok(args.join(" "))
+	}).with_command("fps", |_| {
+		Ok(format!("{} fps", get_fps()))
+	});
+	console.log(UiLogLevel::Info, "Console ready, type 'help' for commands");
+	ui.add_child(UiElement::Console(Box::new(console)));
+
+	let mut bg_color = ui.theme.accent[0];
 
 	loop {
-		let win_size = (window::screen_width(), window::screen_height());
+		// record frame time
+		if let Some(UiElement::Plot(plot)) = ui.find_element_content_mut(20) {
+			plot.push(0, get_frame_time() * 1000.0);
+		}
+		// ui scale, ctrl + / - to zoom and ctrl 0 to follow the display again
+		if is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) {
+			if is_key_pressed(KeyCode::Equal) {
+				ui.set_scale(Some((ui.scale() + 0.25).min(3.0)));
+			} else if is_key_pressed(KeyCode::Minus) {
+				ui.set_scale(Some((ui.scale() - 0.25).max(0.5)));
+			} else if is_key_pressed(KeyCode::Key0) {
+				ui.set_scale(None);
+			}
+		}
 		if let Some(elem) = ui.update() {
 			match elem {
-				UiElement::Button(e) => {
-					if e.event == UiAction::LClick {
-						println!("Clicked btn {}", e.id);
+				UiElement::Button(e) if e.event == UiAction::LRelease => {
+					ui.toast(&format!("Clicked button {}", e.id), UiToastKind::Info, 3.0);
+					if let Some(UiElement::Console(c)) = ui.find_element_content_mut(21) {
+						c.log(UiLogLevel::Debug, &format!("button {} clicked", e.id));
+					}
+				}
+				UiElement::Toggle(e) if e.changed => {
+					let state = if e.checked { "on" } else { "off" };
+					ui.toast(&format!("Switched {} {}", e.id, state), UiToastKind::Success, 2.0);
+				}
+				UiElement::NumberInput(e) if e.changed && e.id == 11 => {
+					ui.toast(&format!("Spawn count set to {}", e.value), UiToastKind::Info, 1.5);
+				}
+				UiElement::ColorPicker(e) if e.changed => {
+					bg_color = e.color;
+				}
+				UiElement::Text(e) => {
+					if let Some(link) = &e.link_clicked {
+						ui.toast(&format!("Opened link '{}'", link), UiToastKind::Info, 2.0);
+					}
+				}
+				UiElement::Canvas(e) if e.event == UiAction::LRelease => {
+					let (x, y) = e.mouse_local;
+					ui.toast(&format!("Canvas clicked at {:.0}, {:.0}", x, y), UiToastKind::Info, 2.0);
+				}
+				UiElement::Box(e) => {
+					if let UiDragEvent::End(dx, dy) = e.drag_event {
+						if let Some(UiElement::Console(c)) = ui.find_element_content_mut(21) {
+							c.log(UiLogLevel::Debug, &format!("box {} dragged by {:.0}, {:.0}", e.id, dx, dy));
+						}
 					}
-					if e.event == UiAction::LRelease {
-						println!("Released btn {}", e.id);
+				}
+				UiElement::Window(e) => {
+					match e.window_event {
+						UiWindowEvent::Close => {
+							ui.toast(&format!("Closed window {}", e.id), UiToastKind::Warning, 3.0);
+						}
+						UiWindowEvent::Move | UiWindowEvent::Resize => {
+							let b = e.bounds();
+							let msg = format!("Window {} at {:.0}, {:.0}", e.id, b.x, b.y);
+							ui.toast(&msg, UiToastKind::Info, 2.0);
+						}
+						_ => ()
 					}
 				}
 				_ => ()
@@ -171,12 +481,8 @@ async fn main() {
 
 		// start render
 		clear_background(bg_color);
-		// draw circle
-		draw_poly(win_size.0 / 2.0 + 3.0, win_size.1 / 2.0 + 2.0, 64, 106.0, 0.0, BLACK);
-		draw_poly(win_size.0 / 2.0, win_size.1 / 2.0, 64, 100.0, 0.0, RED);
 		// draw ui
 		ui.render();
-		fps_counter.update();
 
 		// delay to next frame
 		std::thread::sleep(Duration::from_micros(6000));
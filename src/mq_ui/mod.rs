@@ -8,23 +8,107 @@ mod ui_util;
 pub use ui_util::*;
 mod ui_theme;
 pub use ui_theme::UiTheme;
+pub use ui_theme::UiTexture;
 mod ui_root;
 pub use ui_root::UiRoot;
+mod ui_layout;
+pub use ui_layout::UiLayout;
+pub use ui_layout::UiJustify;
+pub use ui_layout::UiCrossAlign;
+pub use ui_layout::UiFlex;
+pub use ui_layout::UiTrack;
+pub use ui_layout::UiGridCell;
+pub(crate) use ui_layout::UiFlexItem;
+pub(crate) use ui_layout::UiGridItem;
+pub(crate) use ui_layout::flex_layout;
+pub(crate) use ui_layout::grid_layout;
+mod ui_drag;
+pub use ui_drag::UiDragBounds;
+pub use ui_drag::UiDragAxis;
+pub use ui_drag::UiDragEvent;
+pub use ui_drag::UiDrag;
+pub(crate) use ui_drag::UiDragState;
 mod ui_box;
 pub use ui_box::UiBoxParams;
 pub use ui_box::UiBox;
+mod ui_rich_text;
+pub use ui_rich_text::UiTextStyle;
+pub use ui_rich_text::UiTextSpan;
+pub use ui_rich_text::parse_markup;
+mod ui_text_layout;
+pub use ui_text_layout::UiTextLayoutParams;
+pub use ui_text_layout::UiTextLayout;
+pub use ui_text_layout::UiTextAlign;
+pub use ui_text_layout::UiTextVAlign;
+pub use ui_text_layout::UiGlyph;
+pub use ui_text_layout::UiTextRun;
 mod ui_text;
 pub use ui_text::UiTextParams;
 pub use ui_text::UiText;
 mod ui_button;
 pub use ui_button::UiButtonParams;
 pub use ui_button::UiButton;
+pub use ui_button::UiIconSide;
 mod ui_input;
 pub use ui_input::UiInputParams;
 pub use ui_input::UiInput;
+pub use ui_input::UiInputFilter;
 mod ui_radio;
 pub use ui_radio::UiRadioParams;
 pub use ui_radio::UiRadio;
+mod ui_window;
+pub use ui_window::UiWindowParams;
+pub use ui_window::UiWindowEvent;
+pub use ui_window::UiWindow;
+mod ui_toggle;
+pub use ui_toggle::UiToggleParams;
+pub use ui_toggle::UiToggle;
+mod ui_image;
+pub use ui_image::UiImageParams;
+pub use ui_image::UiImage;
+mod ui_number_input;
+pub use ui_number_input::UiNumberInputParams;
+pub use ui_number_input::UiNumberInput;
+mod ui_color_picker;
+pub use ui_color_picker::UiColorPickerParams;
+pub use ui_color_picker::UiColorPicker;
+mod ui_toast;
+pub use ui_toast::UiToastKind;
+pub use ui_toast::UiToast;
+mod ui_split;
+pub use ui_split::UiSplitParams;
+pub use ui_split::UiSplitDir;
+pub use ui_split::UiSplitPane;
+pub use ui_split::UiSplit;
+mod ui_canvas;
+pub use ui_canvas::UiCanvasParams;
+pub use ui_canvas::UiCanvasDraw;
+pub use ui_canvas::UiDrawFn;
+pub use ui_canvas::UiCanvas;
+mod ui_plot;
+pub use ui_plot::UiPlotParams;
+pub use ui_plot::UiPlotStyle;
+pub use ui_plot::UiPlotSeries;
+pub use ui_plot::UiRingBuffer;
+pub use ui_plot::UiPlot;
+mod ui_perf_overlay;
+pub use ui_perf_overlay::UiPerfOverlayParams;
+pub use ui_perf_overlay::UiPerfOverlay;
+mod ui_console;
+pub use ui_console::UiConsoleParams;
+pub use ui_console::UiLogLevel;
+pub use ui_console::UiLogLine;
+pub use ui_console::UiCommandFn;
+pub use ui_console::UiConsole;
+mod ui_collapsible;
+pub use ui_collapsible::UiCollapsibleParams;
+pub use ui_collapsible::UiCollapsible;
+mod ui_progress;
+pub use ui_progress::UiProgressParams;
+pub use ui_progress::UiProgress;
+mod ui_spinner;
+pub use ui_spinner::UiSpinnerParams;
+pub use ui_spinner::UiSpinner;
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub enum UiAction{ None, Hover, Hold, LClickOuter, LClick, RClick, LRelease, RRelease }
@@ -32,6 +116,16 @@ pub enum UiAction{ None, Hover, Hold, LClickOuter, LClick, RClick, LRelease, RRe
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub enum UiMouseAction{ None, Down, Hold, Release }
 
+/// mouse and timing state shared by every element during an update
+#[derive(Debug, Clone)]
+pub(crate) struct UiFrameInput {
+  pub mouse_pos: (f32, f32),
+  pub mouse_delta: (f32, f32),
+  pub l_mouse: UiMouseAction,
+  pub r_mouse: UiMouseAction,
+  pub time_delta: f32,
+}
+
 #[derive(Debug, Clone)]
 pub enum UiElement {
   Box(UiBox),
@@ -39,6 +133,131 @@ pub enum UiElement {
   Button(UiButton),
   Input(UiInput),
   Radio(UiRadio),
+  Window(UiWindow),
+  Toggle(UiToggle),
+  Image(UiImage),
+  NumberInput(Box<UiNumberInput>),
+  ColorPicker(UiColorPicker),
+  Toast(UiToast),
+  Split(Box<UiSplit>),
+  Canvas(UiCanvas),
+  Plot(UiPlot),
+  Console(Box<UiConsole>),
+  Collapsible(Box<UiCollapsible>),
+  Progress(UiProgress),
+  Spinner(UiSpinner),
+}
+impl UiElement {
+  pub fn id(&self) -> u32 {
+    match self {
+      UiElement::Box(e) => e.id,
+      UiElement::Text(e) => e.id,
+      UiElement::Button(e) => e.id,
+      UiElement::Input(e) => e.id,
+      UiElement::Radio(e) => e.id,
+      UiElement::Window(e) => e.id,
+      UiElement::Toggle(e) => e.id,
+      UiElement::Image(e) => e.id,
+      UiElement::NumberInput(e) => e.id,
+      UiElement::ColorPicker(e) => e.id,
+      UiElement::Toast(e) => e.id,
+      UiElement::Split(e) => e.id,
+      UiElement::Canvas(e) => e.id,
+      UiElement::Plot(e) => e.id,
+      UiElement::Console(e) => e.id,
+      UiElement::Collapsible(e) => e.id,
+      UiElement::Progress(e) => e.id,
+      UiElement::Spinner(e) => e.id,
+    }
+  }
+  pub fn bounds(&self) -> Rect {
+    match self {
+      UiElement::Box(e) => e.bounds(),
+      UiElement::Text(e) => e.bounds(),
+      UiElement::Button(e) => e.bounds(),
+      UiElement::Input(e) => e.bounds(),
+      UiElement::Radio(e) => e.bounds(),
+      UiElement::Window(e) => e.bounds(),
+      UiElement::Toggle(e) => e.bounds(),
+      UiElement::Image(e) => e.bounds(),
+      UiElement::NumberInput(e) => e.bounds(),
+      UiElement::ColorPicker(e) => e.bounds(),
+      UiElement::Toast(e) => e.bounds(),
+      UiElement::Split(e) => e.bounds(),
+      UiElement::Canvas(e) => e.bounds(),
+      UiElement::Plot(e) => e.bounds(),
+      UiElement::Console(e) => e.bounds(),
+      UiElement::Collapsible(e) => e.bounds(),
+      UiElement::Progress(e) => e.bounds(),
+      UiElement::Spinner(e) => e.bounds(),
+    }
+  }
+  pub(crate) fn rel_bounds(&self) -> Option<UiRect> {
+    match self {
+      UiElement::Box(e) => Some(e.rel_bounds),
+      UiElement::Text(e) => Some(e.rel_bounds),
+      UiElement::Button(e) => Some(e.rel_bounds),
+      UiElement::Input(e) => Some(e.rel_bounds),
+      UiElement::Radio(e) => Some(e.rel_bounds),
+      UiElement::Window(e) => Some(e.rel_bounds),
+      UiElement::Toggle(e) => Some(e.rel_bounds),
+      UiElement::Image(e) => Some(e.rel_bounds),
+      UiElement::NumberInput(e) => Some(e.rel_bounds),
+      UiElement::ColorPicker(e) => Some(e.rel_bounds),
+      UiElement::Split(e) => Some(e.rel_bounds),
+      UiElement::Canvas(e) => Some(e.rel_bounds),
+      UiElement::Plot(e) => Some(e.rel_bounds),
+      UiElement::Console(e) => Some(e.rel_bounds),
+      UiElement::Collapsible(e) => Some(e.rel_bounds),
+      UiElement::Progress(e) => Some(e.rel_bounds),
+      UiElement::Spinner(e) => Some(e.rel_bounds),
+      // toasts are placed by the root
+      UiElement::Toast(_) => None,
+    }
+  }
+  pub(crate) fn set_rel_bounds(&mut self, rel: UiRect) {
+    match self {
+      UiElement::Box(e) => e.rel_bounds = rel,
+      UiElement::Text(e) => e.rel_bounds = rel,
+      UiElement::Button(e) => e.rel_bounds = rel,
+      UiElement::Input(e) => e.rel_bounds = rel,
+      UiElement::Radio(e) => e.rel_bounds = rel,
+      UiElement::Window(e) => e.rel_bounds = rel,
+      UiElement::Toggle(e) => e.rel_bounds = rel,
+      UiElement::Image(e) => e.rel_bounds = rel,
+      UiElement::NumberInput(e) => e.rel_bounds = rel,
+      UiElement::ColorPicker(e) => e.rel_bounds = rel,
+      UiElement::Split(e) => e.rel_bounds = rel,
+      UiElement::Canvas(e) => e.rel_bounds = rel,
+      UiElement::Plot(e) => e.rel_bounds = rel,
+      UiElement::Console(e) => e.rel_bounds = rel,
+      UiElement::Collapsible(e) => e.rel_bounds = rel,
+      UiElement::Progress(e) => e.rel_bounds = rel,
+      UiElement::Spinner(e) => e.rel_bounds = rel,
+      UiElement::Toast(_) => (),
+    }
+  }
+  /// natural size of the element's content, used by auto sizes
+  pub(crate) fn content_size(&self) -> (f32, f32) {
+    match self {
+      UiElement::Text(e) => e.content_size,
+      UiElement::Button(e) => e.content_size,
+      _ => (0.0, 0.0)
+    }
+  }
+  /// preferred size inside a layout container
+  pub(crate) fn measure(&self, parent_rect: &Rect) -> (f32, f32) {
+    match self {
+      UiElement::Collapsible(e) => e.measure(parent_rect),
+      _ => match self.rel_bounds() {
+        Some(rel) => {
+          let (w, h) = rel.resolve_size(parent_rect, &self.content_size());
+          (w + rel.margin.horizontal(), h + rel.margin.vertical())
+        }
+        None => (self.bounds().w, self.bounds().h)
+      }
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -59,24 +278,37 @@ pub enum UiMetaData {
 pub enum UiSize {
   Px(f32),
   Percent(f32),
+  /// a percentage of the parent plus px, e.g. Calc(1.0, -20.0)
+  Calc(f32, f32),
+  /// size of the content, like the measured text of a label
+  Auto,
+  /// whatever space is left, the rest of the parent or of a row or column
+  Fill,
 }
 impl UiSize {
+  /// the number the size holds in its own unit, px for calc
   pub fn value(&self) -> f32 {
     match self {
       UiSize::Px(x) => *x,
-      UiSize::Percent(x) => *x
+      UiSize::Percent(x) => *x,
+      UiSize::Calc(_, px) => *px,
+      UiSize::Auto | UiSize::Fill => 0.0
     }
   }
   pub fn is_px(&self) -> bool {
-    match self {
-      UiSize::Px(_) => true,
-      _ => false
-    }
+    matches!(self, UiSize::Px(_))
   }
   pub fn is_percent(&self) -> bool {
+    matches!(self, UiSize::Percent(_))
+  }
+  /// length in pixels, with percentages taken of parent_len and auto of content_len
+  pub fn resolve(&self, parent_len: f32, content_len: f32) -> f32 {
     match self {
-      UiSize::Percent(_) => true,
-      _ => false
+      UiSize::Px(x) => *x,
+      UiSize::Percent(x) => *x * parent_len,
+      UiSize::Calc(p, px) => *p * parent_len + *px,
+      UiSize::Auto => content_len,
+      UiSize::Fill => parent_len
     }
   }
 }
@@ -91,6 +323,12 @@ impl Add<f32> for UiSize {
       UiSize::Percent(lhs) => {
         val = UiSize::Percent(lhs + rhs);
       }
+      UiSize::Calc(p, lhs) => {
+        val = UiSize::Calc(p, lhs + rhs);
+      }
+      _ => {
+        val = self;
+      }
     };
     val
   }
@@ -106,6 +344,11 @@ impl AddAssign<f32> for UiSize {
         let val = *lhs + rhs;
         UiSize::Percent(val)
       }
+      UiSize::Calc(p, lhs) => {
+        let val = *lhs + rhs;
+        UiSize::Calc(*p, val)
+      }
+      _ => *self
     };
   }
 }
@@ -120,6 +363,12 @@ impl Sub<f32> for UiSize {
       UiSize::Percent(lhs) => {
         val = UiSize::Percent(lhs - rhs);
       }
+      UiSize::Calc(p, lhs) => {
+        val = UiSize::Calc(p, lhs - rhs);
+      }
+      _ => {
+        val = self;
+      }
     };
     val
   }
@@ -135,6 +384,11 @@ impl SubAssign<f32> for UiSize {
         let val = *lhs - rhs;
         UiSize::Percent(val)
       }
+      UiSize::Calc(p, lhs) => {
+        let val = *lhs - rhs;
+        UiSize::Calc(*p, val)
+      }
+      _ => *self
     };
   }
 }
@@ -152,12 +406,62 @@ pub enum UiAlign {
   BottomRight,
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiImageFit {
+  Stretch,
+  Contain,
+  Cover,
+  None,
+}
+
+/// px spacing on each side of a rect
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct UiEdges {
+  pub top: f32,
+  pub right: f32,
+  pub bottom: f32,
+  pub left: f32,
+}
+impl UiEdges {
+  pub fn all(value: f32) -> Self {
+    Self { top: value, right: value, bottom: value, left: value }
+  }
+  pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+    Self { top: vertical, right: horizontal, bottom: vertical, left: horizontal }
+  }
+  pub fn horizontal(&self) -> f32 {
+    self.left + self.right
+  }
+  pub fn vertical(&self) -> f32 {
+    self.top + self.bottom
+  }
+  /// rect with the edges taken off, never smaller than zero
+  pub fn shrink(&self, rect: &Rect) -> Rect {
+    Rect::new(
+      rect.x + self.left,
+      rect.y + self.top,
+      (rect.w - self.horizontal()).max(0.0),
+      (rect.h - self.vertical()).max(0.0),
+    )
+  }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct UiRect {
   pub x: UiSize,
   pub y: UiSize,
   pub w: UiSize,
   pub h: UiSize,
+  /// space kept free around the rect inside its parent
+  pub margin: UiEdges,
+  /// px limits applied after percentages are resolved
+  pub min_size: (f32, f32),
+  pub max_size: (f32, f32),
+}
+impl Default for UiRect {
+  fn default() -> Self {
+    Self::from_px(0.0, 0.0, 0.0, 0.0)
+  }
 }
 impl UiRect {
   pub fn from_px(x: f32, y: f32, w: f32, h: f32) -> Self {
@@ -166,6 +470,9 @@ impl UiRect {
       y: UiSize::Px(y),
       w: UiSize::Px(w),
       h: UiSize::Px(h),
+      margin: UiEdges::default(),
+      min_size: (0.0, 0.0),
+      max_size: (f32::INFINITY, f32::INFINITY),
     }
   }
   pub fn from_percent(x: f32, y: f32, w: f32, h: f32) -> Self {
@@ -174,6 +481,43 @@ impl UiRect {
       y: UiSize::Percent(y),
       w: UiSize::Percent(w),
       h: UiSize::Percent(h),
+      margin: UiEdges::default(),
+      min_size: (0.0, 0.0),
+      max_size: (f32::INFINITY, f32::INFINITY),
     }
   }
+  pub fn with_margin(mut self, margin: UiEdges) -> Self {
+    self.margin = margin;
+    self
+  }
+  pub fn with_min_size(mut self, w: f32, h: f32) -> Self {
+    self.min_size = (w, h);
+    self
+  }
+  pub fn with_max_size(mut self, w: f32, h: f32) -> Self {
+    self.max_size = (w, h);
+    self
+  }
+  /// part of the parent the rect is placed in, inside its margin
+  pub fn inner_parent(&self, parent_rect: &Rect) -> Rect {
+    self.margin.shrink(parent_rect)
+  }
+  /// px offset from the anchored edges inside parent_rect
+  pub fn resolve_offset(&self, parent_rect: &Rect) -> (f32, f32) {
+    let inner = self.inner_parent(parent_rect);
+    (self.x.resolve(inner.w, 0.0), self.y.resolve(inner.h, 0.0))
+  }
+  /// px size inside parent_rect, clamped to the min and max size
+  ///
+  /// this is the one place sizes get resolved, fill takes what the offset
+  /// leaves of the parent and auto takes content_size.
+  pub fn resolve_size(&self, parent_rect: &Rect, content_size: &(f32, f32)) -> (f32, f32) {
+    let inner = self.inner_parent(parent_rect);
+    let (x, y) = self.resolve_offset(parent_rect);
+    let avail = ((inner.w - x).max(0.0), (inner.h - y).max(0.0));
+    let w = if self.w == UiSize::Fill { avail.0 } else { self.w.resolve(inner.w, content_size.0) };
+    let h = if self.h == UiSize::Fill { avail.1 } else { self.h.resolve(inner.h, content_size.1) };
+    let clamp = |v: f32, min: f32, max: f32| v.min(max).max(min);
+    (clamp(w, self.min_size.0, self.max_size.0), clamp(h, self.min_size.1, self.max_size.1))
+  }
 }
\ No newline at end of file
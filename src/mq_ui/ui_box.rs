@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use crate::mq_ui::*;
 
 /// helper struct for building boxes
@@ -6,7 +7,17 @@ pub struct UiBoxParams<'a> {
   pub pos_size: UiRect,
   pub alignment: UiAlign,
   pub draggable: bool,
+  pub drag: UiDrag,
+  pub raise_on_click: bool,
   pub show_hover: bool,
+  pub clip: bool,
+  pub padding: UiEdges,
+  pub layout: UiLayout,
+  pub gap: f32,
+  pub justify: UiJustify,
+  pub cross_align: UiCrossAlign,
+  pub columns: Vec<UiTrack>,
+  pub rows: Vec<UiTrack>,
   pub theme: Option<&'a UiTheme>,
 }
 impl Default for UiBoxParams<'_> {
@@ -15,7 +26,17 @@ impl Default for UiBoxParams<'_> {
       pos_size: UiRect::from_px(0.0, 0.0, 200.0, 150.0),
       alignment: UiAlign::TopLeft,
       draggable: false,
+      drag: UiDrag::default(),
+      raise_on_click: false,
       show_hover: false,
+      clip: false,
+      padding: UiEdges::default(),
+      layout: UiLayout::Absolute,
+      gap: 0.0,
+      justify: UiJustify::Start,
+      cross_align: UiCrossAlign::Start,
+      columns: Vec::new(),
+      rows: Vec::new(),
       theme: None,
     }
   }
@@ -28,10 +49,29 @@ pub struct UiBox {
   holding: bool,
   pub(crate) children: Vec<UiElement>,
   abs_bounds: Rect,
-  rel_bounds: UiRect,
+  pub(crate) rel_bounds: UiRect,
   alignment: UiAlign,
   draggable: bool,
+  pub drag: UiDrag,
+  pub drag_event: UiDragEvent,
+  drag_state: UiDragState,
+  /// brings self in front of its siblings when clicked
+  pub raise_on_click: bool,
+  pub(crate) raise: bool,
   pub show_hover: bool,
+  /// hides and blocks the parts of children outside of self
+  pub clip: bool,
+  pub padding: UiEdges,
+  pub layout: UiLayout,
+  pub gap: f32,
+  pub justify: UiJustify,
+  /// also aligns grid children within their cells
+  pub cross_align: UiCrossAlign,
+  pub columns: Vec<UiTrack>,
+  pub rows: Vec<UiTrack>,
+  flex: HashMap<u32, UiFlex>,
+  cells: HashMap<u32, UiGridCell>,
+  pub(crate) z_index: HashMap<u32, i32>,
   pub color: Color,
   pub hover_color: Color,
   pub data: Option<UiMetaData>
@@ -40,12 +80,9 @@ impl UiBox {
   pub fn new(id: u32, params: UiBoxParams) -> Self {
     let mut color = GRAY;
     let mut hover_color = LIGHTGRAY;
-    match params.theme {
-      Some(tm) => {
-        color = tm.secondary[0];
-        hover_color = tm.secondary[1];
-      }
-      None => ()
+    if let Some(tm) = params.theme {
+      color = tm.secondary[0];
+      hover_color = tm.secondary[1];
     }
     Self {
       id,
@@ -56,7 +93,23 @@ impl UiBox {
       abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
       alignment: params.alignment,
       draggable: params.draggable,
+      drag: params.drag,
+      drag_event: UiDragEvent::None,
+      drag_state: UiDragState::default(),
+      raise_on_click: params.raise_on_click,
+      raise: false,
       show_hover: params.show_hover,
+      clip: params.clip,
+      padding: params.padding,
+      layout: params.layout,
+      gap: params.gap,
+      justify: params.justify,
+      cross_align: params.cross_align,
+      columns: params.columns,
+      rows: params.rows,
+      flex: HashMap::new(),
+      cells: HashMap::new(),
+      z_index: HashMap::new(),
       color,
       hover_color,
       data: None,
@@ -71,43 +124,40 @@ impl UiBox {
     self.data = Some(meta_data);
     self
   }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  /// positions self and lays out children inside the padding
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    let content_rect = self.content_rect();
+    if self.layout == UiLayout::Absolute {
+      arrange_children(&mut self.children, &content_rect);
+    } else {
+      let slots = self.layout_slots(&content_rect);
+      arrange_slotted_children(&mut self.children, &slots, &content_rect);
+    }
+  }
   pub(crate) fn update(
     &mut self,
     target: &mut Option<UiElement>,
     parent_rect: &Rect,
-    parent_delta: &(f32, f32),
-    mouse_pos: &(f32, f32),
-    mouse_delta: &(f32, f32),
-    l_mouse: &UiMouseAction,
-    r_mouse: &UiMouseAction,
-    time_delta: &f32,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
   ) {
-    let pos_update = update_position_adv(
-      &self.abs_bounds,
-      &self.rel_bounds,
-      parent_rect,
-      parent_delta,
-      &self.alignment,
-      mouse_delta,
-      self.draggable,
-      self.holding,
-    );
-    let size_delta = if self.abs_bounds.w != 0.0 && self.abs_bounds.h != 0.0 {
-      (pos_update.0.w - self.abs_bounds.w, pos_update.0.h - self.abs_bounds.h)
-    } else { (0.0, 0.0) };
-    self.abs_bounds = pos_update.0;
-    self.rel_bounds = pos_update.1;
-    // update children
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // update children, which can't be reached through the clipped away area
+    let content_rect = self.content_rect();
+    let child_frame = if self.clip && !point_in_rect(mouse_pos, &self.abs_bounds) {
+      UiFrameInput { mouse_pos: (f32::MIN, f32::MIN), ..frame.clone() }
+    } else { frame.clone() };
     update_children(
       &mut self.children,
+      &self.z_index,
       target,
-      &self.abs_bounds,
-      &size_delta,
-      mouse_pos,
-      mouse_delta,
-      l_mouse,
-      r_mouse,
-      time_delta,
+      &content_rect,
+      &child_frame,
+      layout_dirty,
     );
     // update self
     let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
@@ -120,10 +170,71 @@ impl UiBox {
       l_mouse,
       r_mouse,
     );
+    if self.raise_on_click && self.event == UiAction::LClick {
+      self.raise = true;
+    }
+    // follow the mouse while held
+    self.drag_event = UiDragEvent::None;
+    if self.draggable {
+      self.drag_event = self.drag_state.update(
+        &self.drag,
+        self.holding,
+        &mut self.abs_bounds,
+        parent_rect,
+        mouse_pos,
+      );
+      if let UiDragEvent::Move(..) = self.drag_event {
+        self.rel_bounds = anchor_offset(&self.abs_bounds, parent_rect, &self.alignment, &self.rel_bounds);
+        *layout_dirty = true;
+      }
+    }
     // clone self into target
     if !action_available && target.is_none() {
       target.replace(UiElement::Box(self.clone()));
     }
+    // drag events are surfaced even if something else is hovered
+    if self.drag_event != UiDragEvent::None {
+      target.replace(UiElement::Box(self.clone()));
+    }
+  }
+  /// area children are laid out in, inside the padding
+  pub fn content_rect(&self) -> Rect {
+    self.padding.shrink(&self.abs_bounds)
+  }
+  fn layout_slots(&self, content_rect: &Rect) -> Vec<Rect> {
+    if self.layout == UiLayout::Grid {
+      let items: Vec<UiGridItem> = self.children.iter().map(|elem| UiGridItem {
+        size: elem.measure(content_rect),
+        cell: self.cells.get(&elem.id()).copied(),
+      }).collect();
+      return grid_layout(content_rect, &self.columns, &self.rows, self.gap, self.cross_align, &items);
+    }
+    let items: Vec<UiFlexItem> = self.children.iter().map(|elem| {
+      let size = elem.measure(content_rect);
+      let (mut main, cross) = if self.layout == UiLayout::Column { (size.1, size.0) } else { size };
+      let mut flex = self.flex.get(&elem.id()).copied().unwrap_or_default();
+      // fill starts empty and grows into the free space
+      if let Some(rel) = elem.rel_bounds() {
+        let column = self.layout == UiLayout::Column;
+        let main_size = if column { rel.h } else { rel.w };
+        if main_size == UiSize::Fill {
+          main = if column { rel.margin.vertical() } else { rel.margin.horizontal() };
+          if !self.flex.contains_key(&elem.id()) { flex.grow = 1.0; }
+        }
+      }
+      // grow and shrink stop at the child's size limits, margins included
+      let (min_main, max_main) = match elem.rel_bounds() {
+        Some(rel) => {
+          let column = self.layout == UiLayout::Column;
+          let margin = if column { rel.margin.vertical() } else { rel.margin.horizontal() };
+          let (min, max) = if column { (rel.min_size.1, rel.max_size.1) } else { (rel.min_size.0, rel.max_size.0) };
+          (min + margin, max + margin)
+        }
+        None => (0.0, f32::INFINITY)
+      };
+      UiFlexItem { main, cross, min_main, max_main, flex }
+    }).collect();
+    flex_layout(content_rect, self.layout, self.gap, self.justify, self.cross_align, &items)
   }
   pub(crate) fn render(&mut self, theme: &UiTheme) {
     let active_color = match self.event {
@@ -148,9 +259,32 @@ impl UiBox {
       active_color,
     );
     // render children
+    if self.clip { push_clip_rect(&self.abs_bounds); }
     render_children(&mut self.children, theme, &active_color);
+    if self.clip { pop_clip_rect(); }
   }
   pub fn add_child(&mut self, elem: UiElement) {
     self.children.push(elem);
   }
+  /// adds a child with grow and shrink factors for row and column layouts
+  pub fn add_flex_child(&mut self, elem: UiElement, flex: UiFlex) {
+    self.flex.insert(elem.id(), flex);
+    self.children.push(elem);
+  }
+  pub fn set_flex(&mut self, id: u32, flex: UiFlex) {
+    self.flex.insert(id, flex);
+  }
+  /// adds a child to a grid cell, children without one fill the next free cell
+  pub fn add_grid_child(&mut self, elem: UiElement, cell: UiGridCell) {
+    self.cells.insert(elem.id(), cell);
+    self.children.push(elem);
+  }
+  pub fn set_cell(&mut self, id: u32, cell: UiGridCell) {
+    self.cells.insert(id, cell);
+  }
+  /// children with a higher z-index are drawn above and hit before the rest
+  pub fn set_z_index(&mut self, id: u32, z_index: i32) {
+    self.z_index.insert(id, z_index);
+    sort_by_z_index(&mut self.children, &self.z_index);
+  }
 }
\ No newline at end of file
@@ -1,11 +1,16 @@
 use crate::mq_ui::*;
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiIconSide { Left, Right }
+
 /// helper struct for building buttons
 #[derive(Debug, Clone)]
 pub struct UiButtonParams<'a> {
   pub pos_size: UiRect,
   pub alignment: UiAlign,
   pub text: String,
+  pub icon: Option<UiTexture>,
+  pub icon_side: UiIconSide,
   pub theme: Option<&'a UiTheme>,
 }
 impl Default for UiButtonParams<'_> {
@@ -14,6 +19,8 @@ impl Default for UiButtonParams<'_> {
       pos_size: UiRect::from_px(0.0, 0.0, 100.0, 30.0),
       alignment: UiAlign::TopLeft,
       text: "Button".to_owned(),
+      icon: None,
+      icon_side: UiIconSide::Left,
       theme: None,
     }
   }
@@ -25,9 +32,13 @@ pub struct UiButton {
   pub event: UiAction,
   holding: bool,
   abs_bounds: Rect,
-  rel_bounds: UiRect,
+  pub(crate) rel_bounds: UiRect,
   alignment: UiAlign,
   text: String,
+  pub icon: Option<UiTexture>,
+  pub icon_side: UiIconSide,
+  pub(crate) content_size: (f32, f32),
+  relayout: bool,
   pub color: Color,
   pub hover_color: Color,
   pub hold_color: Color,
@@ -38,13 +49,10 @@ impl UiButton {
     let mut color = GRAY;
     let mut hover_color = LIGHTGRAY;
     let mut hold_color = BLUE;
-    match params.theme {
-      Some(tm) => {
-        color = tm.secondary[0];
-        hover_color = tm.secondary[1];
-        hold_color = tm.secondary[2];
-      }
-      None => ()
+    if let Some(tm) = params.theme {
+      color = tm.secondary[0];
+      hover_color = tm.secondary[1];
+      hold_color = tm.secondary[2];
     };
     Self {
       id,
@@ -54,6 +62,10 @@ impl UiButton {
       abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
       alignment: params.alignment,
       text: params.text,
+      icon: params.icon,
+      icon_side: params.icon_side,
+      content_size: (0.0, 0.0),
+      relayout: false,
       color,
       hover_color,
       hold_color,
@@ -64,28 +76,24 @@ impl UiButton {
     self.data = Some(meta_data);
     self
   }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &self.content_size, parent_rect, &self.alignment);
+  }
   pub(crate) fn update(
     &mut self,
     target: &mut Option<UiElement>,
-    parent_rect: &Rect,
-    parent_delta: &(f32, f32),
-    mouse_pos: &(f32, f32),
-    mouse_delta: &(f32, f32),
-    l_mouse: &UiMouseAction,
-    r_mouse: &UiMouseAction,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
   ) {
-    let pos_update = update_position_adv(
-      &self.abs_bounds,
-      &self.rel_bounds,
-      parent_rect,
-      parent_delta,
-      &self.alignment,
-      mouse_delta,
-      false,
-      self.holding,
-    );
-    self.abs_bounds = pos_update.0;
-    self.rel_bounds = pos_update.1;
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // content measured in the last render changed size
+    if self.relayout {
+      self.relayout = false;
+      *layout_dirty = true;
+    }
     // update self
     let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
     let mut action_available = target.is_none();
@@ -102,75 +110,39 @@ impl UiButton {
       target.replace(UiElement::Button(self.clone()));
     }
   }
-  pub(crate) fn render(&self, theme: &UiTheme) {
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
     let active_color = match self.event {
       UiAction::Hover | UiAction::LClick => self.hover_color,
       UiAction::Hold | UiAction::LRelease => self.hold_color,
       _ => self.color
     };
     // draw pill
-    draw_poly(
-      self.abs_bounds.x + self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h / 2.0,
-      36,
-      self.abs_bounds.h / 2.0,
-      0.0,
-      active_color
-    );
-    draw_poly(
-      self.abs_bounds.x + self.abs_bounds.w - self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h / 2.0,
-      36,
-      self.abs_bounds.h / 2.0,
-      0.0,
-      active_color
-    );
-    draw_poly_lines(
-      self.abs_bounds.x + self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h / 2.0,
-      36,
-      self.abs_bounds.h / 2.0,
-      0.0,
-      1.0,
-      BLACK
-    );
-    draw_poly_lines(
-      self.abs_bounds.x + self.abs_bounds.w - self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h / 2.0,
-      36,
-      self.abs_bounds.h / 2.0,
-      0.0,
-      1.0,
-      BLACK,
-    );
-    draw_rectangle(
-      self.abs_bounds.x + self.abs_bounds.h / 2.0,
-      self.abs_bounds.y,
-      self.abs_bounds.w - self.abs_bounds.h,
-      self.abs_bounds.h,
-      active_color,
-    );
-    draw_line(
-      self.abs_bounds.x + self.abs_bounds.h / 2.0,
-      self.abs_bounds.y - 0.5,
-      self.abs_bounds.x + self.abs_bounds.w - self.abs_bounds.h / 2.0,
-      self.abs_bounds.y - 0.5,
-      1.0,
-      BLACK,
-    );
-    draw_line(
-      self.abs_bounds.x + self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h + 0.5,
-      self.abs_bounds.x + self.abs_bounds.w - self.abs_bounds.h / 2.0,
-      self.abs_bounds.y + self.abs_bounds.h + 0.5,
-      1.0,
-      BLACK,
-    );
-    // calculate text pos
+    draw_pill(&self.abs_bounds, active_color);
+    draw_pill_lines(&self.abs_bounds, 1.0, BLACK);
+    // calculate content pos
     let txt_size = measure_text(&self.text, theme.font.as_ref(), theme.font_size, 1.0);
-    let txt_x = self.abs_bounds.x + (self.abs_bounds.w - txt_size.width) / 2.0;
+    let icon_size = if self.icon.is_some() { self.abs_bounds.h - 10.0 } else { 0.0 };
+    let gap = if self.icon.is_some() && !self.text.is_empty() { 6.0 } else { 0.0 };
+    let content_w = icon_size + gap + txt_size.width;
+    let content_x = self.abs_bounds.x + (self.abs_bounds.w - content_w) / 2.0;
+    // room for the rounded ends, used by auto sizes
+    let content_size = ((content_w + self.abs_bounds.h).ceil(), (txt_size.height + 14.0).ceil());
+    if content_size != self.content_size {
+      self.content_size = content_size;
+      self.relayout = true;
+    }
+    let (icon_x, txt_x) = match self.icon_side {
+      UiIconSide::Left => (content_x, content_x + icon_size + gap),
+      UiIconSide::Right => (content_x + txt_size.width + gap, content_x),
+    };
+    // draw icon
+    if let Some(handle) = self.icon {
+      let icon_rect = Rect::new(icon_x, self.abs_bounds.y + 5.0, icon_size, icon_size);
+      draw_texture_fit(theme, handle, &icon_rect, None, &UiImageFit::Contain, WHITE);
+    }
+    // draw text
     let txt_y = self.abs_bounds.y + txt_size.height + (self.abs_bounds.h - txt_size.height) / 2.0;
-    draw_text_ex(&self.text, txt_x, txt_y, TextParams {
+    draw_ui_text(&self.text, txt_x, txt_y, TextParams {
       font: theme.font.as_ref(),
       font_size: theme.font_size,
       color: contrast_color(&active_color),
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::mq_ui::*;
+
+/// signature of canvas draw callbacks
+pub type UiCanvasDraw = dyn Fn(&Rect, &UiTheme);
+
+/// user draw callback, receives the canvas bounds and the root theme
+#[derive(Clone)]
+pub struct UiDrawFn(Rc<UiCanvasDraw>);
+impl UiDrawFn {
+  pub fn new<F>(func: F) -> Self
+  where F: Fn(&Rect, &UiTheme) + 'static {
+    Self(Rc::new(func))
+  }
+}
+impl fmt::Debug for UiDrawFn {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("UiDrawFn")
+  }
+}
+
+/// helper struct for building canvases
+#[derive(Debug, Clone)]
+pub struct UiCanvasParams {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub draw: Option<UiDrawFn>,
+}
+impl Default for UiCanvasParams {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      alignment: UiAlign::TopLeft,
+      draw: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiCanvas {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  pub draw: Option<UiDrawFn>,
+  pub mouse_local: (f32, f32),
+  pub data: Option<UiMetaData>
+}
+impl UiCanvas {
+  pub fn new(id: u32, params: UiCanvasParams) -> Self {
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      draw: params.draw,
+      mouse_local: (0.0, 0.0),
+      data: None,
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // mouse position relative to the top left corner
+    self.mouse_local = (mouse_pos.0 - self.abs_bounds.x, mouse_pos.1 - self.abs_bounds.y);
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Canvas(self.clone()));
+    }
+  }
+  pub(crate) fn render(&self, theme: &UiTheme) {
+    if let Some(draw) = &self.draw {
+      push_clip_rect(&self.abs_bounds);
+      (draw.0)(&self.abs_bounds, theme);
+      pop_clip_rect();
+    }
+  }
+}
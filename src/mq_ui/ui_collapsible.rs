@@ -0,0 +1,221 @@
+use crate::mq_ui::*;
+
+/// helper struct for building collapsible sections
+#[derive(Debug, Clone)]
+pub struct UiCollapsibleParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub title: String,
+  pub header_height: f32,
+  pub expanded: bool,
+  pub group: Option<u32>,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiCollapsibleParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 200.0, 120.0),
+      alignment: UiAlign::TopLeft,
+      title: "Section".to_owned(),
+      header_height: 28.0,
+      expanded: true,
+      group: None,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiCollapsible {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  full_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  pub(crate) content: UiBox,
+  open_amount: f32,
+  header_event: UiAction,
+  pub title: String,
+  pub header_height: f32,
+  pub expanded: bool,
+  pub changed: bool,
+  /// sections sharing a group behave as an accordion within their parent
+  pub group: Option<u32>,
+  pub header_color: Color,
+  pub header_hover_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiCollapsible {
+  pub fn new(id: u32, params: UiCollapsibleParams) -> Self {
+    let mut header_color = GRAY;
+    let mut header_hover_color = LIGHTGRAY;
+    if let Some(tm) = params.theme {
+      header_color = tm.secondary[2];
+      header_hover_color = tm.secondary[3];
+    };
+    let content = UiBox::new(internal_id(), UiBoxParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      theme: params.theme,
+      ..Default::default()
+    });
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      full_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      content,
+      open_amount: if params.expanded { 1.0 } else { 0.0 },
+      header_event: UiAction::None,
+      title: params.title,
+      header_height: params.header_height,
+      expanded: params.expanded,
+      changed: false,
+      group: params.group,
+      header_color,
+      header_hover_color,
+      data: None,
+    }
+  }
+  pub fn with<F>(mut self, func: F) -> Self
+  where F: Fn(&mut UiBox) {
+    func(&mut self.content);
+    self
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn content(&mut self) -> &mut UiBox {
+    &mut self.content
+  }
+  /// current bounds, shrinking with the collapse animation
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  /// header plus the visible part of the content
+  pub(crate) fn measure(&self, parent_rect: &Rect) -> (f32, f32) {
+    let (w, full_h) = self.rel_bounds.resolve_size(parent_rect, &(0.0, 0.0));
+    let header_h = self.header_height.min(full_h);
+    let eased = self.open_amount * self.open_amount * (3.0 - 2.0 * self.open_amount);
+    let margin = self.rel_bounds.margin;
+    (w + margin.horizontal(), header_h + (full_h - header_h) * eased + margin.vertical())
+  }
+  pub fn toggle(&mut self) {
+    self.expanded = !self.expanded;
+  }
+  pub(crate) fn header_hovered(&self) -> bool {
+    matches!(self.header_event, UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease)
+  }
+  fn header_rect(&self) -> Rect {
+    let b = self.full_bounds;
+    Rect::new(b.x, b.y, b.w, self.header_height.min(b.h))
+  }
+  fn content_rect(&self) -> Rect {
+    let b = self.full_bounds;
+    let h = self.header_height.min(b.h);
+    Rect::new(b.x, b.y + h, b.w, b.h - h)
+  }
+  /// positions self at full height and lays out the content below the header
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.full_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    self.update_visible_bounds();
+    let content_rect = self.content_rect();
+    self.content.arrange(&content_rect);
+  }
+  fn update_visible_bounds(&mut self) {
+    let eased = self.open_amount * self.open_amount * (3.0 - 2.0 * self.open_amount);
+    let content_h = self.content_rect().h;
+    self.abs_bounds = Rect::new(
+      self.full_bounds.x,
+      self.full_bounds.y,
+      self.full_bounds.w,
+      self.full_bounds.h - content_h + content_h * eased,
+    );
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // animate height
+    let goal = if self.expanded { 1.0 } else { 0.0 };
+    let step = time_delta * 7.0;
+    let prev_amount = self.open_amount;
+    self.open_amount = if self.open_amount < goal { (self.open_amount + step).min(goal) }
+      else { (self.open_amount - step).max(goal) };
+    if self.open_amount != prev_amount {
+      self.update_visible_bounds();
+      *layout_dirty = true;
+    }
+    let content_rect = self.content_rect();
+    let visible_h = self.abs_bounds.h - (self.full_bounds.h - content_rect.h);
+    // update content, ignoring the mouse over its hidden part
+    let action_free = target.is_none();
+    if self.open_amount > 0.0 {
+      let visible = Rect::new(content_rect.x, content_rect.y, content_rect.w, visible_h);
+      let content_mouse = if point_in_rect(mouse_pos, &visible) { *mouse_pos } else { (f32::MIN, f32::MIN) };
+      let content_frame = UiFrameInput { mouse_pos: content_mouse, ..frame.clone() };
+      self.content.update(target, &content_rect, &content_frame, layout_dirty);
+    }
+    // update header
+    let inbounds = point_in_rect(mouse_pos, &self.header_rect());
+    let mut action_available = target.is_none();
+    self.header_event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.header_event,
+      l_mouse,
+      r_mouse,
+    );
+    self.event = self.header_event.clone();
+    self.changed = false;
+    if self.header_event == UiAction::LRelease {
+      self.toggle();
+      self.changed = true;
+    }
+    // clone self into target
+    if action_free && !action_available {
+      target.replace(UiElement::Collapsible(Box::new(self.clone())));
+    }
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    let b = self.abs_bounds;
+    // content, clipped to the animated height
+    if b.h > self.header_height {
+      let content_rect = self.content_rect();
+      let visible = Rect::new(content_rect.x, content_rect.y, content_rect.w, b.h - self.header_height);
+      push_clip_rect(&visible);
+      self.content.render(theme);
+      pop_clip_rect();
+    }
+    // header
+    let header = self.header_rect();
+    let color = if self.header_hovered() { self.header_hover_color } else { self.header_color };
+    draw_rectangle(header.x, header.y, header.w, header.h, color);
+    let text_color = contrast_color(&color);
+    // chevron points right when collapsed and down when expanded
+    let center = vec2(header.x + header.h / 2.0, header.y + header.h / 2.0);
+    let angle = self.open_amount * std::f32::consts::FRAC_PI_2;
+    let (sin, cos) = angle.sin_cos();
+    let rotate = |x: f32, y: f32| center + vec2(x * cos - y * sin, x * sin + y * cos);
+    let size = header.h * 0.18;
+    draw_triangle(rotate(size, 0.0), rotate(-size * 0.6, -size), rotate(-size * 0.6, size), text_color);
+    // title
+    let txt_size = measure_text(&self.title, theme.font.as_ref(), theme.font_size, 1.0);
+    let txt_y = header.y + txt_size.offset_y + (header.h - txt_size.height) / 2.0;
+    draw_ui_text(&self.title, header.x + header.h, txt_y, TextParams {
+      font: theme.font.as_ref(),
+      font_size: theme.font_size,
+      color: text_color,
+      ..Default::default()
+    });
+  }
+}
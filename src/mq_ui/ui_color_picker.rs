@@ -0,0 +1,298 @@
+use crate::mq_ui::*;
+
+const PAD: f32 = 8.0;
+const GAP: f32 = 6.0;
+const BAR_SIZE: f32 = 16.0;
+const HEX_H: f32 = 26.0;
+const SWATCH_SIZE: f32 = 18.0;
+const SWATCH_GAP: f32 = 4.0;
+
+/// helper struct for building color pickers
+#[derive(Debug, Clone)]
+pub struct UiColorPickerParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub color: Color,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiColorPickerParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 220.0, 280.0),
+      alignment: UiAlign::TopLeft,
+      color: WHITE,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum UiColorDrag { None, Area, Hue, Alpha }
+
+#[derive(Debug, Clone)]
+pub struct UiColorPicker {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  hex_input: UiInput,
+  drag: UiColorDrag,
+  hue: f32,
+  sat: f32,
+  val: f32,
+  pub color: Color,
+  pub changed: bool,
+  // theme colors from the last render, so clicks pick what is on screen
+  swatches: Vec<Color>,
+  pub bg_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiColorPicker {
+  pub fn new(id: u32, params: UiColorPickerParams) -> Self {
+    let mut bg_color = GRAY;
+    if let Some(tm) = params.theme {
+      bg_color = tm.secondary[1];
+    };
+    let mut hex_input = UiInput::new(internal_id(), UiInputParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      placeholder: "#RRGGBB".to_owned(),
+      filter: UiInputFilter::Hex,
+      theme: params.theme,
+      ..Default::default()
+    });
+    hex_input.input = color_to_hex(&params.color);
+    let (hue, sat, val) = color_to_hsv(&params.color);
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      hex_input,
+      drag: UiColorDrag::None,
+      hue,
+      sat,
+      val,
+      color: params.color,
+      changed: false,
+      swatches: Vec::new(),
+      bg_color,
+      data: None,
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn set_color(&mut self, color: Color) {
+    let (hue, sat, val) = color_to_hsv(&color);
+    // keep hue for greys so the area doesn't jump back to red
+    if sat > 0.0 && val > 0.0 { self.hue = hue; }
+    self.sat = sat;
+    self.val = val;
+    self.color = color;
+    self.hex_input.input = color_to_hex(&color);
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    let hex_rect = self.hex_rect();
+    self.hex_input.arrange(&hex_rect);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // update hex input
+    let action_free = target.is_none();
+    let prev_hex = self.hex_input.input.clone();
+    self.hex_input.update(target, frame);
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = action_free;
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    let prev_color = self.color;
+    if self.event == UiAction::LClick {
+      self.drag = if point_in_rect(mouse_pos, &self.area_rect()) { UiColorDrag::Area }
+        else if point_in_rect(mouse_pos, &self.hue_rect()) { UiColorDrag::Hue }
+        else if point_in_rect(mouse_pos, &self.alpha_rect()) { UiColorDrag::Alpha }
+        else { UiColorDrag::None };
+      // pick from swatches
+      let swatch = self.swatch_rects().iter().position(|r| point_in_rect(mouse_pos, r));
+      if let Some(idx) = swatch {
+        self.set_color(self.swatches[idx]);
+      }
+    }
+    if !self.holding { self.drag = UiColorDrag::None; }
+    // drag markers
+    match self.drag {
+      UiColorDrag::Area => {
+        let area = self.area_rect();
+        self.sat = ((mouse_pos.0 - area.x) / area.w).clamp(0.0, 1.0);
+        self.val = 1.0 - ((mouse_pos.1 - area.y) / area.h).clamp(0.0, 1.0);
+      }
+      UiColorDrag::Hue => {
+        let bar = self.hue_rect();
+        self.hue = ((mouse_pos.1 - bar.y) / bar.h).clamp(0.0, 0.999);
+      }
+      UiColorDrag::Alpha => {
+        let bar = self.alpha_rect();
+        self.color.a = ((mouse_pos.0 - bar.x) / bar.w).clamp(0.0, 1.0);
+      }
+      UiColorDrag::None => ()
+    }
+    if self.drag != UiColorDrag::None {
+      let color = hsv_to_color(self.hue, self.sat, self.val, self.color.a);
+      self.color = color;
+      self.hex_input.input = color_to_hex(&color);
+    } else if self.hex_input.input != prev_hex {
+      // typed hex values
+      if let Some(color) = hex_to_color(&self.hex_input.input) {
+        let input = self.hex_input.input.clone();
+        self.set_color(color);
+        self.hex_input.input = input;
+      }
+    }
+    self.changed = self.color != prev_color;
+    // clone self into target, changes are surfaced even when not hovered
+    if self.changed || (action_free && !action_available) {
+      target.replace(UiElement::ColorPicker(self.clone()));
+    }
+  }
+  pub(crate) fn text_hovered(&self) -> bool {
+    matches!(self.hex_input.event, UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease)
+  }
+  fn area_rect(&self) -> Rect {
+    let b = self.abs_bounds;
+    let fixed_h = 2.0 * PAD + 3.0 * GAP + BAR_SIZE + HEX_H + self.swatch_rows_h();
+    Rect::new(
+      b.x + PAD,
+      b.y + PAD,
+      (b.w - 2.0 * PAD - GAP - BAR_SIZE).max(0.0),
+      (b.h - fixed_h).max(0.0),
+    )
+  }
+  fn hue_rect(&self) -> Rect {
+    let area = self.area_rect();
+    Rect::new(area.x + area.w + GAP, area.y, BAR_SIZE, area.h)
+  }
+  fn alpha_rect(&self) -> Rect {
+    let area = self.area_rect();
+    Rect::new(area.x, area.y + area.h + GAP, self.abs_bounds.w - 2.0 * PAD, BAR_SIZE)
+  }
+  fn hex_rect(&self) -> Rect {
+    let alpha = self.alpha_rect();
+    Rect::new(alpha.x, alpha.y + alpha.h + GAP, (alpha.w - HEX_H - GAP).max(0.0), HEX_H)
+  }
+  fn preview_rect(&self) -> Rect {
+    let hex = self.hex_rect();
+    Rect::new(hex.x + hex.w + GAP, hex.y, HEX_H, HEX_H)
+  }
+  /// swatches per row, wrapping at the picker's inner width
+  fn swatch_columns(&self) -> usize {
+    let inner_w = self.abs_bounds.w - 2.0 * PAD;
+    (((inner_w + SWATCH_GAP) / (SWATCH_SIZE + SWATCH_GAP)).floor() as usize).max(1)
+  }
+  fn swatch_rows_h(&self) -> f32 {
+    let rows = self.swatches.len().div_ceil(self.swatch_columns()).max(1);
+    rows as f32 * (SWATCH_SIZE + SWATCH_GAP) - SWATCH_GAP
+  }
+  fn swatch_rects(&self) -> Vec<Rect> {
+    let hex = self.hex_rect();
+    let y = hex.y + hex.h + GAP;
+    let columns = self.swatch_columns();
+    (0..self.swatches.len())
+      .map(|i| {
+        let (col, row) = (i % columns, i / columns);
+        Rect::new(
+          hex.x + col as f32 * (SWATCH_SIZE + SWATCH_GAP),
+          y + row as f32 * (SWATCH_SIZE + SWATCH_GAP),
+          SWATCH_SIZE,
+          SWATCH_SIZE,
+        )
+      })
+      .collect()
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    let mut swatches = vec![theme.primary];
+    swatches.extend_from_slice(&theme.secondary);
+    swatches.extend_from_slice(&theme.accent);
+    // a different swatch count moves the hex field
+    if swatches != self.swatches {
+      self.swatches = swatches;
+      let hex_rect = self.hex_rect();
+      self.hex_input.arrange(&hex_rect);
+    }
+    let b = self.abs_bounds;
+    draw_rectangle(b.x, b.y, b.w, b.h, self.bg_color);
+    // saturation / value area
+    let area = self.area_rect();
+    let hue_color = hsv_to_color(self.hue, 1.0, 1.0, 1.0);
+    draw_gradient_rect(&area, WHITE, hue_color, hue_color, WHITE);
+    draw_gradient_rect(&area, BLANK, BLANK, BLACK, BLACK);
+    draw_rectangle_lines(area.x, area.y, area.w, area.h, 1.0, BLACK);
+    let marker = (area.x + self.sat * area.w, area.y + (1.0 - self.val) * area.h);
+    draw_poly_lines(marker.0, marker.1, 16, 5.0, 0.0, 1.5, contrast_color(&self.color));
+    // hue bar
+    let bar = self.hue_rect();
+    for i in 0..6 {
+      let seg_h = bar.h / 6.0;
+      let top = hsv_to_color(i as f32 / 6.0, 1.0, 1.0, 1.0);
+      let bottom = hsv_to_color((i + 1) as f32 / 6.0, 1.0, 1.0, 1.0);
+      let seg = Rect::new(bar.x, bar.y + i as f32 * seg_h, bar.w, seg_h);
+      draw_gradient_rect(&seg, top, top, bottom, bottom);
+    }
+    draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 1.0, BLACK);
+    let hue_y = bar.y + self.hue * bar.h;
+    draw_rectangle_lines(bar.x - 2.0, hue_y - 2.0, bar.w + 4.0, 4.0, 1.5, BLACK);
+    // alpha bar
+    let bar = self.alpha_rect();
+    draw_checkerboard(&bar, bar.h / 2.0);
+    let opaque = adjust_alpha(&self.color, 1.0);
+    draw_gradient_rect(&bar, BLANK, opaque, opaque, BLANK);
+    draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 1.0, BLACK);
+    let alpha_x = bar.x + self.color.a * bar.w;
+    draw_rectangle_lines(alpha_x - 2.0, bar.y - 2.0, 4.0, bar.h + 4.0, 1.5, BLACK);
+    // hex field and preview
+    self.hex_input.render(theme);
+    let preview = self.preview_rect();
+    draw_checkerboard(&preview, preview.h / 4.0);
+    draw_rectangle(preview.x, preview.y, preview.w, preview.h, self.color);
+    draw_rectangle_lines(preview.x, preview.y, preview.w, preview.h, 1.0, BLACK);
+    // theme swatches
+    for (rect, color) in self.swatch_rects().iter().zip(self.swatches.iter()) {
+      draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color);
+      draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, BLACK);
+    }
+  }
+}
+
+fn draw_checkerboard(rect: &Rect, cell: f32) {
+  draw_rectangle(rect.x, rect.y, rect.w, rect.h, LIGHTGRAY);
+  let cols = (rect.w / cell).ceil() as u32;
+  let rows = (rect.h / cell).ceil() as u32;
+  for row in 0..rows {
+    for col in 0..cols {
+      if (row + col) % 2 == 0 { continue; }
+      let x = rect.x + col as f32 * cell;
+      let y = rect.y + row as f32 * cell;
+      let w = cell.min(rect.x + rect.w - x);
+      let h = cell.min(rect.y + rect.h - y);
+      draw_rectangle(x, y, w, h, GRAY);
+    }
+  }
+}
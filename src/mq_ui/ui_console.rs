@@ -0,0 +1,390 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::mq_ui::*;
+
+const PAD: f32 = 6.0;
+const INPUT_H: f32 = 28.0;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum UiLogLevel { Debug, Info, Warning, Error }
+
+#[derive(Debug, Clone)]
+pub struct UiLogLine {
+  pub level: UiLogLevel,
+  pub text: String,
+  // lowercased once for filtering
+  text_lower: String,
+}
+
+/// console command callback, receives the arguments after the command name.
+/// `Ok` output is logged as info, `Err` as an error
+#[derive(Clone)]
+pub struct UiCommandFn(Rc<UiCommand>);
+type UiCommand = dyn Fn(&[&str]) -> Result<String, String>;
+impl UiCommandFn {
+  pub fn new<F>(func: F) -> Self
+  where F: Fn(&[&str]) -> Result<String, String> + 'static {
+    Self(Rc::new(func))
+  }
+}
+impl fmt::Debug for UiCommandFn {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("UiCommandFn")
+  }
+}
+
+/// helper struct for building consoles
+#[derive(Debug, Clone)]
+pub struct UiConsoleParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub hotkey: KeyCode,
+  pub open: bool,
+  pub max_lines: usize,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiConsoleParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 0.4),
+      alignment: UiAlign::TopLeft,
+      hotkey: KeyCode::GraveAccent,
+      open: false,
+      max_lines: 500,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiConsole {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  input: UiInput,
+  lines: VecDeque<UiLogLine>,
+  handlers: HashMap<String, UiCommandFn>,
+  history: Vec<String>,
+  history_idx: Option<usize>,
+  // lines scrolled up from the bottom, 0 follows new output
+  scroll: usize,
+  slide: f32,
+  pub hotkey: KeyCode,
+  pub open: bool,
+  pub max_lines: usize,
+  pub min_level: UiLogLevel,
+  pub filter: String,
+  pub font_size: u16,
+  pub submitted: Option<String>,
+  pub bg_color: Color,
+  pub text_color: Color,
+  pub debug_color: Color,
+  pub warning_color: Color,
+  pub error_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiConsole {
+  pub fn new(id: u32, params: UiConsoleParams) -> Self {
+    let mut bg_color = Color::new(0.0, 0.0, 0.0, 0.85);
+    let mut text_color = WHITE;
+    let mut warning_color = YELLOW;
+    let mut error_color = RED;
+    let mut font_size = 18;
+    if let Some(tm) = params.theme {
+      font_size = tm.font_size;
+      bg_color = adjust_alpha(&tm.secondary[4], 0.92);
+      text_color = contrast_color(&tm.secondary[4]);
+      warning_color = tm.warning_color;
+      error_color = tm.error_color;
+    };
+    let input = UiInput::new(internal_id(), UiInputParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      placeholder: "Enter a command, 'help' lists them".to_owned(),
+      theme: params.theme,
+      ..Default::default()
+    });
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      input,
+      lines: VecDeque::new(),
+      handlers: HashMap::new(),
+      history: Vec::new(),
+      history_idx: None,
+      scroll: 0,
+      slide: if params.open { 1.0 } else { 0.0 },
+      hotkey: params.hotkey,
+      open: params.open,
+      max_lines: params.max_lines.max(1),
+      min_level: UiLogLevel::Debug,
+      filter: String::new(),
+      font_size,
+      submitted: None,
+      bg_color,
+      text_color,
+      debug_color: adjust_alpha(&text_color, 0.55),
+      warning_color,
+      error_color,
+      data: None,
+    }
+  }
+  pub fn with_command<F>(mut self, name: &str, func: F) -> Self
+  where F: Fn(&[&str]) -> Result<String, String> + 'static {
+    self.register(name, func);
+    self
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn register<F>(&mut self, name: &str, func: F)
+  where F: Fn(&[&str]) -> Result<String, String> + 'static {
+    self.handlers.insert(name.to_owned(), UiCommandFn::new(func));
+  }
+  pub fn log(&mut self, level: UiLogLevel, text: &str) {
+    for line in text.lines() {
+      self.lines.push_back(UiLogLine { level, text: line.to_owned(), text_lower: line.to_lowercase() });
+      // keep the view still while scrolled up
+      if self.scroll > 0 && self.matches(self.lines.back().unwrap(), &self.filter.to_lowercase()) {
+        self.scroll += 1;
+      }
+    }
+    while self.lines.len() > self.max_lines {
+      self.lines.pop_front();
+    }
+  }
+  pub fn clear(&mut self) {
+    self.lines.clear();
+    self.scroll = 0;
+  }
+  pub fn toggle(&mut self) {
+    self.open = !self.open;
+    self.input.is_active = self.open;
+  }
+  /// runs a command line through the built in and registered commands
+  pub fn execute(&mut self, line: &str) {
+    let line = line.trim();
+    if line.is_empty() { return; }
+    self.log(UiLogLevel::Debug, &format!("> {}", line));
+    if self.history.last().map(|h| h.as_str()) != Some(line) {
+      self.history.push(line.to_owned());
+    }
+    let mut parts = line.split_whitespace();
+    let name = parts.next().unwrap_or_default();
+    let args: Vec<&str> = parts.collect();
+    match name {
+      "help" => {
+        let mut names: Vec<&String> = self.handlers.keys().collect();
+        names.sort();
+        let list: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let msg = format!("built in: help, clear, filter [text], level [debug|info|warning|error]\ncommands: {}", list.join(", "));
+        self.log(UiLogLevel::Info, &msg);
+      }
+      "clear" => self.clear(),
+      "filter" => {
+        self.filter = args.join(" ");
+        self.scroll = 0;
+      }
+      "level" => {
+        let level = match args.first().map(|a| a.to_lowercase()).as_deref() {
+          Some("debug") => Some(UiLogLevel::Debug),
+          Some("info") => Some(UiLogLevel::Info),
+          Some("warning") => Some(UiLogLevel::Warning),
+          Some("error") => Some(UiLogLevel::Error),
+          _ => None
+        };
+        match level {
+          Some(l) => {
+            self.min_level = l;
+            self.scroll = 0;
+          }
+          None => self.log(UiLogLevel::Error, "usage: level [debug|info|warning|error]")
+        }
+      }
+      _ => {
+        match self.handlers.get(name).cloned() {
+          Some(handler) => {
+            match (handler.0)(&args) {
+              Ok(out) => if !out.is_empty() { self.log(UiLogLevel::Info, &out); }
+              Err(err) => self.log(UiLogLevel::Error, &err)
+            }
+          }
+          None => self.log(UiLogLevel::Error, &format!("unknown command '{}'", name))
+        }
+      }
+    }
+  }
+  /// filter is expected in lowercase
+  fn matches(&self, line: &UiLogLine, filter: &str) -> bool {
+    line.level >= self.min_level && (filter.is_empty() || line.text_lower.contains(filter))
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    let input_rect = self.input_rect();
+    self.input.arrange(&input_rect);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    self.submitted = None;
+    // the hotkey is left to other text fields while they are typed into
+    let toggled = is_key_pressed(self.hotkey) && !other_input_active(self.input.id);
+    if toggled { self.toggle(); }
+    // slide in and out
+    let goal = if self.open { 1.0 } else { 0.0 };
+    let step = time_delta * 6.0;
+    let prev_slide = self.slide;
+    self.slide = if self.slide < goal { (self.slide + step).min(goal) } else { (self.slide - step).max(goal) };
+    if self.slide != prev_slide {
+      let input_rect = self.input_rect();
+      self.input.arrange(&input_rect);
+    }
+    if self.slide <= 0.0 {
+      self.event = UiAction::None;
+      return;
+    }
+    // update command input
+    let action_free = target.is_none();
+    let prev_input = self.input.input.clone();
+    self.input.update(target, frame);
+    // don't type the hotkey into the input
+    if toggled { self.input.input = prev_input; }
+    if self.input.is_active {
+      if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+        let line = std::mem::take(&mut self.input.input);
+        self.history_idx = None;
+        self.scroll = 0;
+        self.execute(&line);
+        self.submitted = Some(line);
+      }
+      // recall history
+      if is_key_pressed(KeyCode::Up) && !self.history.is_empty() {
+        let idx = match self.history_idx {
+          Some(i) => i.saturating_sub(1),
+          None => self.history.len() - 1
+        };
+        self.history_idx = Some(idx);
+        self.input.input = self.history[idx].clone();
+      }
+      if is_key_pressed(KeyCode::Down) {
+        match self.history_idx {
+          Some(i) if i + 1 < self.history.len() => {
+            self.history_idx = Some(i + 1);
+            self.input.input = self.history[i + 1].clone();
+          }
+          Some(_) => {
+            self.history_idx = None;
+            self.input.clear();
+          }
+          None => ()
+        }
+      }
+    }
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.bounds());
+    let mut action_available = action_free;
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    // scroll back through the log
+    if inbounds && action_free {
+      let wheel = mouse_wheel().1;
+      let visible = self.visible_lines();
+      let filter = self.filter.to_lowercase();
+      let total = self.lines.iter().filter(|l| self.matches(l, &filter)).count();
+      let max_scroll = total.saturating_sub(visible);
+      if wheel > 0.0 { self.scroll = (self.scroll + 3).min(max_scroll); }
+      else if wheel < 0.0 { self.scroll = self.scroll.saturating_sub(3); }
+    }
+    // clone self into target, submitted commands are surfaced even when not hovered
+    if self.submitted.is_some() || (action_free && !action_available) {
+      target.replace(UiElement::Console(Box::new(self.clone())));
+    }
+  }
+  pub(crate) fn text_hovered(&self) -> bool {
+    matches!(self.input.event, UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease)
+  }
+  /// bounds shifted by the drop down animation
+  pub fn bounds(&self) -> Rect {
+    let b = self.abs_bounds;
+    let eased = 1.0 - (1.0 - self.slide).powi(3);
+    Rect::new(b.x, b.y - b.h * (1.0 - eased), b.w, b.h)
+  }
+  fn input_rect(&self) -> Rect {
+    let b = self.bounds();
+    Rect::new(b.x + PAD, b.y + b.h - PAD - INPUT_H, (b.w - 2.0 * PAD).max(0.0), INPUT_H)
+  }
+  fn log_rect(&self) -> Rect {
+    let b = self.bounds();
+    Rect::new(b.x + PAD, b.y + PAD, (b.w - 2.0 * PAD).max(0.0), (b.h - 3.0 * PAD - INPUT_H).max(0.0))
+  }
+  fn line_height(&self) -> f32 {
+    self.font_size as f32 * 1.2
+  }
+  fn visible_lines(&self) -> usize {
+    (self.log_rect().h / self.line_height()).floor() as usize
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    if self.slide <= 0.0 { return; }
+    let b = self.bounds();
+    draw_rectangle(b.x, b.y, b.w, b.h, self.bg_color);
+    draw_line(b.x, b.y + b.h, b.x + b.w, b.y + b.h, 2.0, theme.accent[0]);
+    // log lines, newest at the bottom
+    let area = self.log_rect();
+    let line_h = self.line_height();
+    let filter = self.filter.to_lowercase();
+    let shown: Vec<&UiLogLine> = self.lines.iter().filter(|l| self.matches(l, &filter)).collect();
+    let visible = self.visible_lines();
+    let end = shown.len().saturating_sub(self.scroll);
+    let start = end.saturating_sub(visible);
+    push_clip_rect(&area);
+    let metrics = measure_text("Ag", theme.font.as_ref(), self.font_size, 1.0);
+    let mut y = area.y + area.h - (end - start) as f32 * line_h;
+    for line in shown[start..end].iter() {
+      let color = match line.level {
+        UiLogLevel::Debug => self.debug_color,
+        UiLogLevel::Info => self.text_color,
+        UiLogLevel::Warning => self.warning_color,
+        UiLogLevel::Error => self.error_color,
+      };
+      let baseline = y + metrics.offset_y + (line_h - metrics.height) / 2.0;
+      draw_ui_text(&line.text, area.x, baseline, TextParams {
+        font: theme.font.as_ref(),
+        font_size: self.font_size,
+        color,
+        ..Default::default()
+      });
+      y += line_h;
+    }
+    pop_clip_rect();
+    // scroll indicator
+    if self.scroll > 0 {
+      let txt = format!("-- {} more below --", self.scroll);
+      let w = measure_text(&txt, theme.font.as_ref(), self.font_size, 1.0).width;
+      draw_ui_text(&txt, area.x + area.w - w, area.y + metrics.offset_y, TextParams {
+        font: theme.font.as_ref(),
+        font_size: self.font_size,
+        color: self.warning_color,
+        ..Default::default()
+      });
+    }
+    self.input.render(theme);
+  }
+}
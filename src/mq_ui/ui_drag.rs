@@ -0,0 +1,86 @@
+use crate::mq_ui::*;
+
+/// area a dragged element has to stay inside
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiDragBounds { None, Parent, Screen, Rect(Rect) }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiDragAxis { Both, X, Y }
+
+/// drag progress, move carries the movement since the last frame and end
+/// carries the movement since the start
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiDragEvent { None, Start, Move(f32, f32), End(f32, f32) }
+
+/// how a draggable element follows the mouse
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UiDrag {
+  pub bounds: UiDragBounds,
+  pub axis: UiDragAxis,
+  /// grid size positions snap to, measured from the parent's corner, 0 to move freely
+  pub snap: f32,
+}
+impl Default for UiDrag {
+  fn default() -> Self {
+    Self {
+      bounds: UiDragBounds::None,
+      axis: UiDragAxis::Both,
+      snap: 0.0,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UiDragState {
+  active: bool,
+  start: Rect,
+  origin: (f32, f32),
+}
+impl UiDragState {
+  /// moves a held element after the mouse, keeping its offset in sync
+  pub(crate) fn update(
+    &mut self,
+    drag: &UiDrag,
+    holding: bool,
+    abs_bounds: &mut Rect,
+    parent_rect: &Rect,
+    mouse_pos: &(f32, f32),
+  ) -> UiDragEvent {
+    if !self.active {
+      if !holding { return UiDragEvent::None; }
+      self.active = true;
+      self.start = *abs_bounds;
+      self.origin = *mouse_pos;
+      return UiDragEvent::Start;
+    }
+    if !holding {
+      self.active = false;
+      return UiDragEvent::End(abs_bounds.x - self.start.x, abs_bounds.y - self.start.y);
+    }
+    let mut next = self.start;
+    if drag.axis != UiDragAxis::Y { next.x += mouse_pos.0 - self.origin.0; }
+    if drag.axis != UiDragAxis::X { next.y += mouse_pos.1 - self.origin.1; }
+    // only the free axes snap, a locked axis stays where it started
+    if drag.snap > 0.0 && drag.axis != UiDragAxis::Y {
+      next.x = parent_rect.x + ((next.x - parent_rect.x) / drag.snap).round() * drag.snap;
+    }
+    if drag.snap > 0.0 && drag.axis != UiDragAxis::X {
+      next.y = parent_rect.y + ((next.y - parent_rect.y) / drag.snap).round() * drag.snap;
+    }
+    let area = match drag.bounds {
+      UiDragBounds::None => None,
+      UiDragBounds::Parent => Some(*parent_rect),
+      UiDragBounds::Screen => Some(ui_screen_rect()),
+      UiDragBounds::Rect(r) => Some(r),
+    };
+    // keep the whole element inside, pinning to the near edge if it doesn't fit
+    if let Some(a) = area {
+      next.x = next.x.min(a.x + a.w - next.w).max(a.x);
+      next.y = next.y.min(a.y + a.h - next.h).max(a.y);
+    }
+    let delta = (next.x - abs_bounds.x, next.y - abs_bounds.y);
+    if delta == (0.0, 0.0) { return UiDragEvent::None; }
+    *abs_bounds = next;
+    UiDragEvent::Move(delta.0, delta.1)
+  }
+}
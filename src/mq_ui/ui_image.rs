@@ -0,0 +1,93 @@
+use crate::mq_ui::*;
+
+/// helper struct for building images
+#[derive(Debug, Clone)]
+pub struct UiImageParams {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub texture: Option<UiTexture>,
+  pub fit: UiImageFit,
+  pub tint: Color,
+  pub source: Option<Rect>,
+}
+impl Default for UiImageParams {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 64.0, 64.0),
+      alignment: UiAlign::TopLeft,
+      texture: None,
+      fit: UiImageFit::Contain,
+      tint: WHITE,
+      source: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiImage {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  pub texture: Option<UiTexture>,
+  pub fit: UiImageFit,
+  pub tint: Color,
+  pub source: Option<Rect>,
+  pub data: Option<UiMetaData>
+}
+impl UiImage {
+  pub fn new(id: u32, params: UiImageParams) -> Self {
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      texture: params.texture,
+      fit: params.fit,
+      tint: params.tint,
+      source: params.source,
+      data: None,
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Image(self.clone()));
+    }
+  }
+  pub(crate) fn render(&self, theme: &UiTheme) {
+    if let Some(handle) = self.texture {
+      draw_texture_fit(theme, handle, &self.abs_bounds, self.source, &self.fit, self.tint);
+    }
+  }
+}
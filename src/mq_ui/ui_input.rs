@@ -1,12 +1,16 @@
 use std::collections::HashSet;
 use crate::mq_ui::*;
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiInputFilter { Any, Integer, Float, Hex }
+
 /// helper struct for building inputs
 #[derive(Debug, Clone)]
 pub struct UiInputParams<'a> {
   pub pos_size: UiRect,
   pub alignment: UiAlign,
   pub placeholder: String,
+  pub filter: UiInputFilter,
   pub theme: Option<&'a UiTheme>,
 }
 impl Default for UiInputParams<'_> {
@@ -15,6 +19,7 @@ impl Default for UiInputParams<'_> {
       pos_size: UiRect::from_px(0.0, 0.0, 200.0, 30.0),
       alignment: UiAlign::TopLeft,
       placeholder: "Input".to_owned(),
+      filter: UiInputFilter::Any,
       theme: None,
     }
   }
@@ -26,20 +31,19 @@ pub struct UiInput {
   pub event: UiAction,
   holding: bool,
   abs_bounds: Rect,
-  rel_bounds: UiRect,
+  pub(crate) rel_bounds: UiRect,
   alignment: UiAlign,
   pub is_active: bool,
   pub input: String,
   pub placeholder: String,
+  pub filter: UiInputFilter,
   blink_counter: f32,
   show_blink: bool,
   bksp_cooldown: f32,
-  target: RenderTarget,
   pub data: Option<UiMetaData>
 }
 impl UiInput {
   pub fn new(id: u32, params: UiInputParams) -> Self {
-    let target = render_target_msaa(2000, 2000, 4);
     Self {
       id,
       event: UiAction::None,
@@ -50,10 +54,10 @@ impl UiInput {
       is_active: false,
       input: String::new(),
       placeholder: params.placeholder,
+      filter: params.filter,
       blink_counter: 0.0,
       show_blink: false,
       bksp_cooldown: 0.0,
-      target,
       data: None,
     }
   }
@@ -61,29 +65,18 @@ impl UiInput {
     self.data = Some(meta_data);
     self
   }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
   pub(crate) fn update(
     &mut self,
     target: &mut Option<UiElement>,
-    parent_rect: &Rect,
-    parent_delta: &(f32, f32),
-    mouse_pos: &(f32, f32),
-    mouse_delta: &(f32, f32),
-    l_mouse: &UiMouseAction,
-    r_mouse: &UiMouseAction,
-    time_delta: &f32,
+    frame: &UiFrameInput,
   ) {
-    let pos_update = update_position_adv(
-      &self.abs_bounds,
-      &self.rel_bounds,
-      parent_rect,
-      parent_delta,
-      &self.alignment,
-      mouse_delta,
-      false,
-      self.holding,
-    );
-    self.abs_bounds = pos_update.0;
-    self.rel_bounds = pos_update.1;
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
     // update self
     let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
     let mut action_available = target.is_none();
@@ -107,6 +100,7 @@ impl UiInput {
     };
     // take input
     if self.is_active {
+      note_active_input(self.id);
       // register key inputs
       let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
       if is_key_pressed(KeyCode::Backspace) && !self.input.is_empty() {
@@ -126,7 +120,7 @@ impl UiInput {
       for key_code in pressed.iter() {
         let cc = key_code_to_char(key_code);
         let c = if shift { cc.1 } else { cc.0 };
-        self.input += c;
+        if self.accepts(c) { self.input += c; }
       }
       // update blinker state
       self.blink_counter += time_delta;
@@ -151,60 +145,57 @@ impl UiInput {
     };
     if self.is_active { active_color = theme.secondary[3] };
     let txt_size = measure_text(&self.input, theme.font.as_ref(), theme.font_size, 1.0);
-    self.draw_to_target(theme, &(txt_size.width, txt_size.height), active_color);
-    // draw target
-    draw_texture_ex(&self.target.texture, self.abs_bounds.x, self.abs_bounds.y, WHITE, DrawTextureParams {
-      source: Some(Rect::new(0.0, 0.0, self.abs_bounds.w, self.abs_bounds.h)),
-      ..Default::default()
-    });
-    // draw blinker
-    if self.is_active && self.show_blink {
-      let mut blinker_x = self.abs_bounds.x + txt_size.width + 3.0;
-      if txt_size.width > self.abs_bounds.w {
-        // scroll text so its right aligned
-        blinker_x = self.abs_bounds.x + self.abs_bounds.w - 3.0;
-      }
-      let blinker_y = self.abs_bounds.y + 2.0;
-      draw_line(blinker_x, blinker_y, blinker_x, blinker_y + self.abs_bounds.h - 4.0, 2.0, contrast_color(&active_color));
-    }
-    // draw border
-    draw_rectangle_lines(self.abs_bounds.x, self.abs_bounds.y, self.abs_bounds.w, self.abs_bounds.h, 1.5, BLACK);
-  }
-  fn draw_to_target(&mut self, theme: &UiTheme, txt_size: &(f32, f32), active_color: Color) {
-    // draw to target
-    set_camera(&Camera2D {
-      zoom: vec2(0.001, 0.001),
-      target: vec2(1000.0, 1000.0),
-      render_target: Some(self.target.clone()),
-      ..Default::default()
-    });
-    clear_background(active_color);
-    // draw text
-    let mut txt_x = 3.0;
-    let txt_y = self.abs_bounds.h - 10.0;
+    let b = self.abs_bounds;
+    draw_rectangle(b.x, b.y, b.w, b.h, active_color);
+    // draw text, clipped to the field so long input scrolls out of view
+    push_clip_rect(&b);
+    let mut txt_x = b.x + 3.0;
+    let txt_y = b.y + b.h - 10.0;
     let text_color = contrast_color(&active_color);
-    if txt_size.0 > self.abs_bounds.w {
+    if txt_size.width > b.w {
       // scroll text so its right aligned
-      txt_x = self.abs_bounds.w - txt_size.0 - 3.0;
+      txt_x = b.x + b.w - txt_size.width - 3.0;
     }
     if self.is_active || !self.input.is_empty() {
-      draw_text_ex(&self.input, txt_x, txt_y, TextParams {
+      draw_ui_text(&self.input, txt_x, txt_y, TextParams {
         font: theme.font.as_ref(),
         font_size: theme.font_size,
-        color: contrast_color(&active_color),
+        color: text_color,
         ..Default::default()
       });
     } else if !self.placeholder.is_empty() {
-      draw_text_ex(&self.placeholder, txt_x, txt_y, TextParams {
+      draw_ui_text(&self.placeholder, txt_x, txt_y, TextParams {
         font: theme.font.as_ref(),
         font_size: theme.font_size,
         color: adjust_alpha(&text_color, 0.6),
         ..Default::default()
       });
     }
-
-    // stop drawing to target
-    set_default_camera();
+    pop_clip_rect();
+    // draw blinker
+    if self.is_active && self.show_blink {
+      let mut blinker_x = self.abs_bounds.x + txt_size.width + 3.0;
+      if txt_size.width > self.abs_bounds.w {
+        // scroll text so its right aligned
+        blinker_x = self.abs_bounds.x + self.abs_bounds.w - 3.0;
+      }
+      let blinker_y = self.abs_bounds.y + 2.0;
+      draw_line(blinker_x, blinker_y, blinker_x, blinker_y + self.abs_bounds.h - 4.0, 2.0, contrast_color(&active_color));
+    }
+    // draw border
+    draw_rectangle_lines(self.abs_bounds.x, self.abs_bounds.y, self.abs_bounds.w, self.abs_bounds.h, 1.5, BLACK);
+  }
+  fn accepts(&self, c: &str) -> bool {
+    c.chars().all(|ch| match self.filter {
+      UiInputFilter::Any => true,
+      UiInputFilter::Integer => ch.is_ascii_digit() || (ch == '-' && self.input.is_empty()),
+      UiInputFilter::Float => {
+        ch.is_ascii_digit()
+          || (ch == '-' && self.input.is_empty())
+          || (ch == '.' && !self.input.contains('.'))
+      }
+      UiInputFilter::Hex => ch.is_ascii_hexdigit() || (ch == '#' && self.input.is_empty()),
+    })
   }
   pub fn clear(&mut self) {
     self.input.clear();
@@ -0,0 +1,439 @@
+use crate::mq_ui::*;
+
+/// how a box places its children
+///
+/// absolute children position themselves with their own rects, row and
+/// column children are placed one after another by their container, which
+/// only keeps their sizes. grid children are placed into cells.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiLayout { Absolute, Row, Column, Grid }
+
+/// distribution of children along the main axis
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiJustify { Start, Center, End, SpaceBetween }
+
+/// placement of children across the main axis
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiCrossAlign { Start, Center, End, Stretch }
+
+/// how a child shares the free space of a row or column
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UiFlex {
+  pub grow: f32,
+  pub shrink: f32,
+}
+impl Default for UiFlex {
+  fn default() -> Self {
+    Self {
+      grow: 0.0,
+      shrink: 1.0,
+    }
+  }
+}
+impl UiFlex {
+  pub fn grow(grow: f32) -> Self {
+    Self { grow, ..Default::default() }
+  }
+  pub fn fixed() -> Self {
+    Self { grow: 0.0, shrink: 0.0 }
+  }
+}
+
+/// size of a grid column or row
+///
+/// fractional tracks share the space left over by all other tracks, auto
+/// tracks fit the largest child that sits in them without spanning.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiTrack {
+  Px(f32),
+  Percent(f32),
+  Fr(f32),
+  Auto,
+}
+
+/// cell a child occupies in a grid, zero based
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UiGridCell {
+  pub column: usize,
+  pub row: usize,
+  pub column_span: usize,
+  pub row_span: usize,
+}
+impl UiGridCell {
+  pub fn at(column: usize, row: usize) -> Self {
+    Self { column, row, column_span: 1, row_span: 1 }
+  }
+  pub fn span(mut self, columns: usize, rows: usize) -> Self {
+    self.column_span = columns.max(1);
+    self.row_span = rows.max(1);
+    self
+  }
+}
+
+/// a child's preferred size along both axes, plus its flex factors
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UiFlexItem {
+  pub main: f32,
+  pub cross: f32,
+  pub min_main: f32,
+  pub max_main: f32,
+  pub flex: UiFlex,
+}
+
+/// places items in a row or column inside bounds, returning one rect per item
+pub(crate) fn flex_layout(
+  bounds: &Rect,
+  layout: UiLayout,
+  gap: f32,
+  justify: UiJustify,
+  cross_align: UiCrossAlign,
+  items: &[UiFlexItem],
+) -> Vec<Rect> {
+  if items.is_empty() { return Vec::new(); }
+  let row = layout != UiLayout::Column;
+  let (main_len, cross_len) = if row { (bounds.w, bounds.h) } else { (bounds.h, bounds.w) };
+  // grow or shrink towards the available length. limits win over flex, so
+  // items that hit one are frozen and the rest share the space again
+  let base: Vec<f32> = items.iter().map(|i| i.main.max(0.0)).collect();
+  let gaps = gap * (items.len() - 1) as f32;
+  let growing = main_len - gaps - base.iter().sum::<f32>() > 0.0;
+  let clamp = |size: f32, item: &UiFlexItem| size.min(item.max_main).max(item.min_main);
+  let weight = |idx: usize| {
+    if growing { items[idx].flex.grow.max(0.0) }
+    else { base[idx] * items[idx].flex.shrink.max(0.0) }
+  };
+  let mut frozen: Vec<bool> = (0..items.len()).map(|idx| weight(idx) <= 0.0).collect();
+  let mut sizes: Vec<f32> = base.iter().zip(items).map(|(b, i)| clamp(*b, i)).collect();
+  loop {
+    let free = main_len - gaps - (0..items.len())
+      .map(|idx| if frozen[idx] { sizes[idx] } else { base[idx] })
+      .sum::<f32>();
+    let total: f32 = (0..items.len()).filter(|idx| !frozen[*idx]).map(weight).sum();
+    if total <= 0.0 { break; }
+    let mut clamped = false;
+    for idx in 0..items.len() {
+      if frozen[idx] { continue; }
+      let flexed = (base[idx] + free * weight(idx) / total).max(0.0);
+      sizes[idx] = clamp(flexed, &items[idx]);
+      if sizes[idx] != flexed {
+        frozen[idx] = true;
+        clamped = true;
+      }
+    }
+    if !clamped { break; }
+  }
+  // distribute what is left
+  let free = (main_len - gaps - sizes.iter().sum::<f32>()).max(0.0);
+  let (mut cursor, spacing) = match justify {
+    UiJustify::Start => (0.0, gap),
+    UiJustify::Center => (free / 2.0, gap),
+    UiJustify::End => (free, gap),
+    UiJustify::SpaceBetween => {
+      if items.len() > 1 { (0.0, gap + free / (items.len() - 1) as f32) }
+      else { (0.0, gap) }
+    }
+  };
+  let mut rects = Vec::with_capacity(items.len());
+  for (size, item) in sizes.iter().zip(items) {
+    let cross = if cross_align == UiCrossAlign::Stretch { cross_len } else { item.cross.min(cross_len) };
+    let cross_pos = match cross_align {
+      UiCrossAlign::Start | UiCrossAlign::Stretch => 0.0,
+      UiCrossAlign::Center => (cross_len - cross) / 2.0,
+      UiCrossAlign::End => cross_len - cross,
+    };
+    let rect = if row {
+      Rect::new(bounds.x + cursor, bounds.y + cross_pos, *size, cross)
+    } else {
+      Rect::new(bounds.x + cross_pos, bounds.y + cursor, cross, *size)
+    };
+    rects.push(rect);
+    cursor += size + spacing;
+  }
+  rects
+}
+
+/// a grid child's preferred size and, if given, its cell
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UiGridItem {
+  pub size: (f32, f32),
+  pub cell: Option<UiGridCell>,
+}
+
+/// places items into grid cells inside bounds, returning one rect per item
+///
+/// items without a cell fill the next free cells row by row, rows past the
+/// defined ones are added as auto tracks.
+pub(crate) fn grid_layout(
+  bounds: &Rect,
+  columns: &[UiTrack],
+  rows: &[UiTrack],
+  gap: f32,
+  cell_align: UiCrossAlign,
+  items: &[UiGridItem],
+) -> Vec<Rect> {
+  if items.is_empty() { return Vec::new(); }
+  let column_count = columns.len().max(1);
+  // resolve cells, auto placed items skip explicitly taken ones. spans are
+  // public fields, so zero spans from struct literals count as one
+  let explicit: Vec<Option<UiGridCell>> = items.iter().map(|i| i.cell.map(|c| c.span(c.column_span, c.row_span))).collect();
+  let taken: Vec<(usize, usize)> = explicit.iter().flatten().flat_map(|c| {
+    (c.row..c.row + c.row_span).flat_map(move |r| (c.column..c.column + c.column_span).map(move |col| (col, r)))
+  }).collect();
+  let mut next = 0;
+  let cells: Vec<UiGridCell> = explicit.iter().map(|cell| match cell {
+    Some(cell) => *cell,
+    None => {
+      while taken.contains(&(next % column_count, next / column_count)) { next += 1; }
+      let cell = UiGridCell::at(next % column_count, next / column_count);
+      next += 1;
+      cell
+    }
+  }).collect();
+  let column_total = cells.iter().map(|c| c.column + c.column_span).max().unwrap_or(0).max(columns.len());
+  let row_total = cells.iter().map(|c| c.row + c.row_span).max().unwrap_or(0).max(rows.len());
+  // track sizes
+  let column_fit: Vec<(usize, usize, f32)> = cells.iter().zip(items).map(|(c, i)| (c.column, c.column_span, i.size.0)).collect();
+  let row_fit: Vec<(usize, usize, f32)> = cells.iter().zip(items).map(|(c, i)| (c.row, c.row_span, i.size.1)).collect();
+  let column_sizes = resolve_tracks(columns, column_total, bounds.w, gap, &column_fit);
+  let row_sizes = resolve_tracks(rows, row_total, bounds.h, gap, &row_fit);
+  let offsets = |sizes: &Vec<f32>| {
+    let mut out = Vec::with_capacity(sizes.len());
+    let mut pos = 0.0;
+    for size in sizes.iter() {
+      out.push(pos);
+      pos += size + gap;
+    }
+    out
+  };
+  let column_pos = offsets(&column_sizes);
+  let row_pos = offsets(&row_sizes);
+  let span_len = |sizes: &Vec<f32>, start: usize, span: usize| {
+    sizes[start..start + span].iter().sum::<f32>() + gap * (span - 1) as f32
+  };
+  // place each item within its cell area
+  cells.iter().zip(items).map(|(cell, item)| {
+    let area = Rect::new(
+      bounds.x + column_pos[cell.column],
+      bounds.y + row_pos[cell.row],
+      span_len(&column_sizes, cell.column, cell.column_span),
+      span_len(&row_sizes, cell.row, cell.row_span),
+    );
+    match cell_align {
+      UiCrossAlign::Stretch => area,
+      _ => {
+        let (w, h) = (item.size.0.min(area.w), item.size.1.min(area.h));
+        let factor = match cell_align {
+          UiCrossAlign::Center => 0.5,
+          UiCrossAlign::End => 1.0,
+          _ => 0.0
+        };
+        Rect::new(area.x + (area.w - w) * factor, area.y + (area.h - h) * factor, w, h)
+      }
+    }
+  }).collect()
+}
+
+/// sizes count tracks along one axis, fit holds (start, span, size) per item
+fn resolve_tracks(tracks: &[UiTrack], count: usize, available: f32, gap: f32, fit: &[(usize, usize, f32)]) -> Vec<f32> {
+  let track = |idx: usize| tracks.get(idx).copied().unwrap_or(UiTrack::Auto);
+  let mut sizes: Vec<f32> = (0..count).map(|idx| match track(idx) {
+    UiTrack::Px(px) => px,
+    UiTrack::Percent(p) => p * available,
+    UiTrack::Fr(_) => 0.0,
+    UiTrack::Auto => {
+      fit.iter().filter(|f| f.0 == idx && f.1 == 1).map(|f| f.2).fold(0.0, f32::max)
+    }
+  }).collect();
+  let total_fr: f32 = (0..count).map(|idx| match track(idx) {
+    UiTrack::Fr(fr) => fr.max(0.0),
+    _ => 0.0
+  }).sum();
+  if total_fr > 0.0 {
+    let gaps = gap * count.saturating_sub(1) as f32;
+    let free = (available - gaps - sizes.iter().sum::<f32>()).max(0.0);
+    for (idx, size) in sizes.iter_mut().enumerate() {
+      if let UiTrack::Fr(fr) = track(idx) { *size = free * fr.max(0.0) / total_fr; }
+    }
+  }
+  sizes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-3
+  }
+
+  fn item(main: f32, flex: UiFlex) -> UiFlexItem {
+    UiFlexItem { main, cross: 10.0, min_main: 0.0, max_main: f32::INFINITY, flex }
+  }
+
+  fn row(width: f32, justify: UiJustify, items: &[UiFlexItem]) -> Vec<Rect> {
+    let bounds = Rect::new(10.0, 20.0, width, 40.0);
+    flex_layout(&bounds, UiLayout::Row, 0.0, justify, UiCrossAlign::Start, items)
+  }
+
+  #[test]
+  fn grow_splits_free_space_by_factor() {
+    let rects = row(300.0, UiJustify::Start, &[item(50.0, UiFlex::grow(1.0)), item(50.0, UiFlex::grow(2.0))]);
+    assert!(close(rects[0].w, 50.0 + 200.0 / 3.0));
+    assert!(close(rects[1].w, 50.0 + 400.0 / 3.0));
+    assert!(close(rects[1].x, rects[0].x + rects[0].w));
+    assert!(close(rects[1].x + rects[1].w, 310.0));
+  }
+
+  #[test]
+  fn shrink_is_weighted_by_size() {
+    let rects = row(100.0, UiJustify::Start, &[item(100.0, UiFlex::default()), item(50.0, UiFlex::default())]);
+    assert!(close(rects[0].w, 100.0 - 50.0 * 100.0 / 150.0));
+    assert!(close(rects[1].w, 50.0 - 50.0 * 50.0 / 150.0));
+  }
+
+  #[test]
+  fn fixed_items_neither_grow_nor_shrink() {
+    let rects = row(100.0, UiJustify::Start, &[item(80.0, UiFlex::fixed()), item(80.0, UiFlex::default())]);
+    assert!(close(rects[0].w, 80.0));
+    assert!(close(rects[1].w, 20.0));
+  }
+
+  #[test]
+  fn max_wins_over_grow_and_the_others_take_the_rest() {
+    let mut capped = item(50.0, UiFlex::grow(1.0));
+    capped.max_main = 80.0;
+    let rects = row(300.0, UiJustify::Start, &[capped, item(50.0, UiFlex::grow(1.0))]);
+    assert!(close(rects[0].w, 80.0));
+    assert!(close(rects[1].w, 220.0));
+    assert!(close(rects[1].x, 90.0));
+    assert!(close(rects[1].x + rects[1].w, 310.0));
+  }
+
+  #[test]
+  fn clamping_repeats_until_nothing_hits_a_limit() {
+    let mut first = item(0.0, UiFlex::grow(1.0));
+    first.max_main = 40.0;
+    let mut second = item(0.0, UiFlex::grow(1.0));
+    second.max_main = 90.0;
+    let items = [first, second, item(0.0, UiFlex::grow(1.0))];
+    let rects = row(300.0, UiJustify::Start, &items);
+    // 100 each, then 130 each for the last two, then the rest for the third
+    assert!(close(rects[0].w, 40.0));
+    assert!(close(rects[1].w, 90.0));
+    assert!(close(rects[2].w, 170.0));
+  }
+
+  #[test]
+  fn min_wins_over_shrink_and_the_others_give_up_more() {
+    let mut floored = item(100.0, UiFlex::default());
+    floored.min_main = 90.0;
+    let rects = row(100.0, UiJustify::Start, &[floored, item(100.0, UiFlex::default())]);
+    assert!(close(rects[0].w, 90.0));
+    assert!(close(rects[1].w, 10.0));
+  }
+
+  #[test]
+  fn space_between_with_one_item_starts_at_the_edge() {
+    let rects = row(200.0, UiJustify::SpaceBetween, &[item(50.0, UiFlex::fixed())]);
+    assert_eq!(rects.len(), 1);
+    assert!(close(rects[0].x, 10.0));
+    assert!(close(rects[0].w, 50.0));
+  }
+
+  #[test]
+  fn space_between_pins_the_outer_items() {
+    let items = [item(40.0, UiFlex::fixed()), item(40.0, UiFlex::fixed()), item(40.0, UiFlex::fixed())];
+    let rects = row(200.0, UiJustify::SpaceBetween, &items);
+    assert!(close(rects[0].x, 10.0));
+    assert!(close(rects[1].x, 10.0 + 80.0));
+    assert!(close(rects[2].x + rects[2].w, 210.0));
+  }
+
+  #[test]
+  fn column_lays_out_along_y_and_stretches_across() {
+    let bounds = Rect::new(0.0, 0.0, 60.0, 200.0);
+    let items = [item(30.0, UiFlex::fixed()), item(30.0, UiFlex::fixed())];
+    let rects = flex_layout(&bounds, UiLayout::Column, 5.0, UiJustify::End, UiCrossAlign::Stretch, &items);
+    assert!(close(rects[0].y, 200.0 - 65.0));
+    assert!(close(rects[1].y, 200.0 - 30.0));
+    assert!(close(rects[0].w, 60.0) && close(rects[0].h, 30.0));
+  }
+
+  fn grid_item(w: f32, h: f32, cell: Option<UiGridCell>) -> UiGridItem {
+    UiGridItem { size: (w, h), cell }
+  }
+
+  fn grid(columns: &[UiTrack], rows: &[UiTrack], gap: f32, items: &[UiGridItem]) -> Vec<Rect> {
+    let bounds = Rect::new(0.0, 0.0, 300.0, 200.0);
+    grid_layout(&bounds, columns, rows, gap, UiCrossAlign::Stretch, items)
+  }
+
+  #[test]
+  fn auto_placement_skips_taken_cells() {
+    let columns = [UiTrack::Px(50.0), UiTrack::Px(50.0)];
+    let rows = [UiTrack::Px(20.0), UiTrack::Px(20.0), UiTrack::Px(20.0)];
+    let items = [
+      grid_item(10.0, 10.0, Some(UiGridCell::at(1, 0).span(1, 2))),
+      grid_item(10.0, 10.0, None),
+      grid_item(10.0, 10.0, None),
+      grid_item(10.0, 10.0, None),
+    ];
+    let rects = grid(&columns, &rows, 0.0, &items);
+    assert!(close(rects[0].x, 50.0) && close(rects[0].h, 40.0));
+    assert!(close(rects[1].x, 0.0) && close(rects[1].y, 0.0));
+    assert!(close(rects[2].x, 0.0) && close(rects[2].y, 20.0));
+    assert!(close(rects[3].x, 0.0) && close(rects[3].y, 40.0));
+  }
+
+  #[test]
+  fn fr_tracks_share_the_leftover_space() {
+    let columns = [UiTrack::Px(100.0), UiTrack::Fr(1.0), UiTrack::Fr(3.0)];
+    let items = [
+      grid_item(10.0, 10.0, None),
+      grid_item(10.0, 10.0, None),
+      grid_item(10.0, 10.0, None),
+    ];
+    let rects = grid(&columns, &[], 10.0, &items);
+    // 300 minus two gaps and the fixed track leaves 180
+    assert!(close(rects[1].w, 45.0));
+    assert!(close(rects[2].w, 135.0));
+    assert!(close(rects[2].x, 100.0 + 10.0 + 45.0 + 10.0));
+  }
+
+  #[test]
+  fn spans_cover_tracks_and_the_gaps_between_them() {
+    let columns = [UiTrack::Px(50.0), UiTrack::Px(70.0), UiTrack::Px(30.0)];
+    let rows = [UiTrack::Px(20.0), UiTrack::Px(40.0)];
+    let items = [grid_item(10.0, 10.0, Some(UiGridCell::at(1, 0).span(2, 2)))];
+    let rects = grid(&columns, &rows, 10.0, &items);
+    assert!(close(rects[0].x, 60.0) && close(rects[0].y, 0.0));
+    assert!(close(rects[0].w, 110.0));
+    assert!(close(rects[0].h, 70.0));
+  }
+
+  #[test]
+  fn auto_tracks_fit_only_unspanned_items() {
+    let columns = [UiTrack::Auto, UiTrack::Auto];
+    let items = [
+      grid_item(40.0, 15.0, None),
+      grid_item(25.0, 30.0, None),
+      grid_item(500.0, 10.0, Some(UiGridCell::at(0, 1).span(2, 1))),
+    ];
+    let rects = grid(&columns, &[], 0.0, &items);
+    assert!(close(rects[0].w, 40.0) && close(rects[1].w, 25.0));
+    // rows past the defined ones are auto sized to their tallest item
+    assert!(close(rects[0].h, 30.0));
+    assert!(close(rects[2].y, 30.0) && close(rects[2].h, 10.0));
+    assert!(close(rects[2].w, 65.0));
+  }
+
+  #[test]
+  fn zero_spans_count_as_one() {
+    let columns = [UiTrack::Px(50.0), UiTrack::Px(70.0)];
+    let zero = UiGridCell { column: 0, row: 0, column_span: 0, row_span: 0 };
+    let items = [grid_item(10.0, 10.0, Some(zero)), grid_item(10.0, 10.0, None)];
+    let rects = grid(&columns, &[UiTrack::Px(20.0)], 10.0, &items);
+    assert!(close(rects[0].w, 50.0) && close(rects[0].h, 20.0));
+    assert!(close(rects[1].x, 60.0) && close(rects[1].y, 0.0));
+  }
+}
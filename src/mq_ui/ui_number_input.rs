@@ -0,0 +1,219 @@
+use crate::mq_ui::*;
+
+/// helper struct for building number inputs
+#[derive(Debug, Clone)]
+pub struct UiNumberInputParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub value: f32,
+  pub min: f32,
+  pub max: f32,
+  pub step: f32,
+  pub precision: usize,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiNumberInputParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 140.0, 30.0),
+      alignment: UiAlign::TopLeft,
+      value: 0.0,
+      min: f32::MIN,
+      max: f32::MAX,
+      step: 1.0,
+      precision: 0,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiNumberInput {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  input: UiInput,
+  dec_btn: UiButton,
+  inc_btn: UiButton,
+  repeat_cooldown: f32,
+  was_active: bool,
+  pub value: f32,
+  pub min: f32,
+  pub max: f32,
+  pub step: f32,
+  pub precision: usize,
+  pub changed: bool,
+  pub data: Option<UiMetaData>
+}
+impl UiNumberInput {
+  pub fn new(id: u32, params: UiNumberInputParams) -> Self {
+    let filter = if params.precision == 0 { UiInputFilter::Integer } else { UiInputFilter::Float };
+    // inner parts get their own ids so their events can't be mistaken for ours
+    let mut input = UiInput::new(internal_id(), UiInputParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      placeholder: String::new(),
+      filter,
+      theme: params.theme,
+      ..Default::default()
+    });
+    let dec_btn = UiButton::new(internal_id(), UiButtonParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      text: "-".to_owned(),
+      theme: params.theme,
+      ..Default::default()
+    });
+    let inc_btn = UiButton::new(internal_id(), UiButtonParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      text: "+".to_owned(),
+      theme: params.theme,
+      ..Default::default()
+    });
+    let value = params.value.clamp(params.min, params.max);
+    input.input = format!("{:.*}", params.precision, value);
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      input,
+      dec_btn,
+      inc_btn,
+      repeat_cooldown: 0.0,
+      was_active: false,
+      value,
+      min: params.min,
+      max: params.max,
+      step: params.step,
+      precision: params.precision,
+      changed: false,
+      data: None,
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  /// current value as integer or float meta data, depending on precision
+  pub fn value_data(&self) -> UiMetaData {
+    if self.precision == 0 { UiMetaData::Integer(self.value.round() as i32) }
+    else { UiMetaData::Float(self.value) }
+  }
+  pub fn set_value(&mut self, value: f32) {
+    self.value = self.round(value.clamp(self.min, self.max));
+    self.input.input = format!("{:.*}", self.precision, self.value);
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    let (dec_rect, input_rect, inc_rect) = self.inner_rects();
+    self.dec_btn.arrange(&dec_rect);
+    self.inc_btn.arrange(&inc_rect);
+    self.input.arrange(&input_rect);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // update inner elements
+    let action_free = target.is_none();
+    // the inner buttons fill fixed rects, so their content never moves anything
+    let mut relayout = false;
+    self.dec_btn.update(target, frame, &mut relayout);
+    self.inc_btn.update(target, frame, &mut relayout);
+    let prev_input = self.input.input.clone();
+    self.input.update(target, frame);
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = action_free;
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    self.changed = false;
+    // step buttons, repeating while held
+    let mut steps = 0.0;
+    let btn_events = [(self.dec_btn.event.clone(), -1.0), (self.inc_btn.event.clone(), 1.0)];
+    for (btn_event, dir) in btn_events {
+      match btn_event {
+        UiAction::LClick => {
+          steps += dir;
+          self.repeat_cooldown = 0.5;
+        }
+        UiAction::Hold => {
+          self.repeat_cooldown -= time_delta;
+          if self.repeat_cooldown <= 0.0 {
+            steps += dir;
+            self.repeat_cooldown = 0.06;
+          }
+        }
+        _ => ()
+      }
+    }
+    // mouse wheel and arrow keys
+    if inbounds && action_free {
+      let wheel = mouse_wheel().1;
+      if wheel > 0.0 { steps += 1.0; }
+      else if wheel < 0.0 { steps -= 1.0; }
+    }
+    if self.input.is_active {
+      if is_key_pressed(KeyCode::Up) { steps += 1.0; }
+      if is_key_pressed(KeyCode::Down) { steps -= 1.0; }
+    }
+    if steps != 0.0 {
+      let prev_value = self.value;
+      self.set_value(self.value + steps * self.step);
+      self.changed = self.value != prev_value;
+    } else if self.input.input != prev_input {
+      // parse typed values without rewriting the text mid-edit
+      if let Ok(v) = self.input.input.parse::<f32>() {
+        let v = self.round(v.clamp(self.min, self.max));
+        self.changed = v != self.value;
+        self.value = v;
+      }
+    }
+    // normalize text when editing finishes
+    if self.was_active && !self.input.is_active {
+      self.set_value(self.value);
+    }
+    self.was_active = self.input.is_active;
+    // clone self into target, changes are surfaced even when not hovered
+    if self.changed || (action_free && !action_available) {
+      target.replace(UiElement::NumberInput(Box::new(self.clone())));
+    }
+  }
+  pub(crate) fn text_hovered(&self) -> bool {
+    matches!(self.input.event, UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease)
+  }
+  fn round(&self, value: f32) -> f32 {
+    let factor = 10f32.powi(self.precision as i32);
+    (value * factor).round() / factor
+  }
+  fn inner_rects(&self) -> (Rect, Rect, Rect) {
+    let b = self.abs_bounds;
+    let btn_w = b.h;
+    let gap = 4.0;
+    (
+      Rect::new(b.x, b.y, btn_w, b.h),
+      Rect::new(b.x + btn_w + gap, b.y, (b.w - 2.0 * (btn_w + gap)).max(0.0), b.h),
+      Rect::new(b.x + b.w - btn_w, b.y, btn_w, b.h),
+    )
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    self.dec_btn.render(theme);
+    self.input.render(theme);
+    self.inc_btn.render(theme);
+  }
+}
@@ -0,0 +1,203 @@
+use crate::mq_ui::*;
+
+const PAD: f32 = 6.0;
+const MARGIN: f32 = 5.0;
+const WIDTH: f32 = 250.0;
+const SPARK_H: f32 = 36.0;
+
+/// helper struct for building performance overlays
+#[derive(Debug, Clone)]
+pub struct UiPerfOverlayParams<'a> {
+  pub corner: UiAlign,
+  pub hotkey: KeyCode,
+  pub visible: bool,
+  pub history: usize,
+  pub refresh_rate: f32,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiPerfOverlayParams<'_> {
+  fn default() -> Self {
+    Self {
+      corner: UiAlign::BottomLeft,
+      hotkey: KeyCode::F3,
+      visible: true,
+      history: 240,
+      refresh_rate: 0.5,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+struct UiPerfStats {
+  fps: f32,
+  low_fps: f32,
+  avg_ms: f32,
+  min_ms: f32,
+  max_ms: f32,
+  update_ms: f32,
+  render_ms: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct UiPerfOverlay {
+  // frame time history lives in the sparkline's only series
+  spark: UiPlot,
+  update_times: UiRingBuffer,
+  render_times: UiRingBuffer,
+  stats: UiPerfStats,
+  since_refresh: f32,
+  pub corner: UiAlign,
+  pub hotkey: KeyCode,
+  pub visible: bool,
+  pub refresh_rate: f32,
+  pub bg_color: Color,
+  pub text_color: Color,
+  pub line_color: Color,
+  pub good_color: Color,
+  pub ok_color: Color,
+  pub bad_color: Color,
+}
+impl UiPerfOverlay {
+  pub fn new(params: UiPerfOverlayParams) -> Self {
+    let mut bg_color = Color::new(0.0, 0.0, 0.0, 0.6);
+    let mut text_color = WHITE;
+    let mut line_color = GREEN;
+    let mut good_color = GREEN;
+    let mut ok_color = YELLOW;
+    let mut bad_color = RED;
+    if let Some(tm) = params.theme {
+      bg_color = adjust_alpha(&tm.secondary[0], 0.85);
+      text_color = contrast_color(&tm.secondary[0]);
+      line_color = tm.accent[0];
+      good_color = tm.success_color;
+      ok_color = tm.warning_color;
+      bad_color = tm.error_color;
+    };
+    let mut spark = UiPlot::new(internal_id(), UiPlotParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      capacity: params.history,
+      grid_lines: 1,
+      show_labels: false,
+      theme: params.theme,
+      ..Default::default()
+    }).with_series("frame", UiPlotStyle::Line);
+    spark.bg_color = adjust_alpha(&text_color, 0.08);
+    Self {
+      spark,
+      update_times: UiRingBuffer::new(params.history),
+      render_times: UiRingBuffer::new(params.history),
+      stats: UiPerfStats::default(),
+      since_refresh: params.refresh_rate,
+      corner: params.corner,
+      hotkey: params.hotkey,
+      visible: params.visible,
+      refresh_rate: params.refresh_rate,
+      bg_color,
+      text_color,
+      line_color,
+      good_color,
+      ok_color,
+      bad_color,
+    }
+  }
+  /// records one frame of timings, all in milliseconds
+  pub(crate) fn update(&mut self, frame_ms: f32, update_ms: f32, render_ms: f32) {
+    if is_key_pressed(self.hotkey) {
+      self.visible = !self.visible;
+    }
+    self.spark.series[0].color = self.line_color;
+    self.spark.push(0, frame_ms);
+    self.update_times.push(update_ms);
+    self.render_times.push(render_ms);
+    // refresh text periodically so it stays readable
+    self.since_refresh += frame_ms / 1000.0;
+    if self.since_refresh >= self.refresh_rate {
+      self.since_refresh = 0.0;
+      self.refresh_stats();
+    }
+  }
+  fn frame_times(&self) -> &UiRingBuffer {
+    &self.spark.series[0].values
+  }
+  fn refresh_stats(&mut self) {
+    let mut sorted: Vec<f32> = self.frame_times().iter().copied().collect();
+    if sorted.is_empty() { return; }
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let len = sorted.len();
+    let avg = sorted.iter().sum::<f32>() / len as f32;
+    // average of the slowest 1% of frames
+    let worst = (len / 100).max(1);
+    let worst_avg = sorted[len - worst..].iter().sum::<f32>() / worst as f32;
+    let avg_of = |buf: &UiRingBuffer| {
+      if buf.is_empty() { 0.0 } else { buf.iter().sum::<f32>() / buf.len() as f32 }
+    };
+    self.stats = UiPerfStats {
+      fps: if avg > 0.0 { 1000.0 / avg } else { 0.0 },
+      low_fps: if worst_avg > 0.0 { 1000.0 / worst_avg } else { 0.0 },
+      avg_ms: avg,
+      min_ms: sorted[0],
+      max_ms: sorted[len - 1],
+      update_ms: avg_of(&self.update_times),
+      render_ms: avg_of(&self.render_times),
+    };
+  }
+  fn fps_color(&self, fps: f32) -> Color {
+    if fps >= 55.0 { self.good_color }
+    else if fps >= 30.0 { self.ok_color }
+    else { self.bad_color }
+  }
+  /// draws in ui pixels, so it has to run under the root's camera
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    if !self.visible { return; }
+    let font_size = (theme.font_size as f32 * 0.75) as u16;
+    let line_h = measure_text("Ag", theme.font.as_ref(), font_size, 1.0);
+    let lines = [
+      (format!("FPS {:.0}", self.stats.fps), self.fps_color(self.stats.fps)),
+      (format!("1% low {:.0}", self.stats.low_fps), self.fps_color(self.stats.low_fps)),
+      (
+        format!(
+          "frame {:.2} ms (min {:.2} / max {:.2})",
+          self.stats.avg_ms, self.stats.min_ms, self.stats.max_ms,
+        ),
+        self.text_color,
+      ),
+      (
+        format!("ui update {:.2} ms, render {:.2} ms", self.stats.update_ms, self.stats.render_ms),
+        self.text_color,
+      ),
+    ];
+    let h = PAD * 3.0 + line_h.height * 3.0 + 4.0 + SPARK_H;
+    let scrn = ui_screen_rect();
+    let (sw, sh) = (scrn.w, scrn.h);
+    let x = match self.corner {
+      UiAlign::TopLeft | UiAlign::CenterLeft | UiAlign::BottomLeft => MARGIN,
+      UiAlign::TopCenter | UiAlign::FullCenter | UiAlign::BottomCenter => (sw - WIDTH) / 2.0,
+      _ => sw - WIDTH - MARGIN
+    };
+    let y = match self.corner {
+      UiAlign::TopLeft | UiAlign::TopCenter | UiAlign::TopRight => MARGIN,
+      UiAlign::CenterLeft | UiAlign::FullCenter | UiAlign::CenterRight => (sh - h) / 2.0,
+      _ => sh - h - MARGIN
+    };
+    draw_rectangle(x, y, WIDTH, h, self.bg_color);
+    // stats text, fps and 1% low share the first row
+    let text_params = |color: Color| TextParams {
+      font: theme.font.as_ref(),
+      font_size,
+      color,
+      ..Default::default()
+    };
+    let mut baseline = y + PAD + line_h.offset_y;
+    draw_ui_text(&lines[0].0, x + PAD, baseline, text_params(lines[0].1));
+    draw_ui_text(&lines[1].0, x + WIDTH / 2.0, baseline, text_params(lines[1].1));
+    for (txt, color) in lines[2..].iter() {
+      baseline += line_h.height + 2.0;
+      draw_ui_text(txt, x + PAD, baseline, text_params(*color));
+    }
+    // frame time sparkline
+    let spark = Rect::new(x + PAD, y + h - PAD - SPARK_H, WIDTH - 2.0 * PAD, SPARK_H);
+    self.spark.arrange(&spark);
+    self.spark.render(theme);
+  }
+}
@@ -0,0 +1,351 @@
+use crate::mq_ui::*;
+
+const PAD: f32 = 6.0;
+// keeps the label count sane for huge or tiny ranges
+const MAX_DIVISIONS: usize = 20;
+
+/// fixed capacity buffer that overwrites the oldest value when full
+#[derive(Debug, Clone)]
+pub struct UiRingBuffer {
+  values: Vec<f32>,
+  capacity: usize,
+  head: usize,
+}
+impl UiRingBuffer {
+  pub fn new(capacity: usize) -> Self {
+    let capacity = capacity.max(1);
+    Self {
+      values: Vec::with_capacity(capacity),
+      capacity,
+      head: 0,
+    }
+  }
+  pub fn push(&mut self, value: f32) {
+    if self.values.len() < self.capacity {
+      self.values.push(value);
+    } else {
+      self.values[self.head] = value;
+      self.head = (self.head + 1) % self.capacity;
+    }
+  }
+  pub fn clear(&mut self) {
+    self.values.clear();
+    self.head = 0;
+  }
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+  /// values from oldest to newest
+  pub fn iter(&self) -> impl Iterator<Item = &f32> {
+    self.values[self.head..].iter().chain(self.values[..self.head].iter())
+  }
+  pub fn last(&self) -> Option<f32> {
+    if self.values.is_empty() { return None; }
+    let idx = (self.head + self.values.len() - 1) % self.values.len();
+    Some(self.values[idx])
+  }
+  /// smallest finite value
+  pub fn min(&self) -> Option<f32> {
+    self.values.iter().copied().filter(|v| v.is_finite()).reduce(f32::min)
+  }
+  /// largest finite value
+  pub fn max(&self) -> Option<f32> {
+    self.values.iter().copied().filter(|v| v.is_finite()).reduce(f32::max)
+  }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiPlotStyle { Line, Bar }
+
+#[derive(Debug, Clone)]
+pub struct UiPlotSeries {
+  pub label: String,
+  pub color: Color,
+  pub style: UiPlotStyle,
+  pub values: UiRingBuffer,
+}
+
+/// helper struct for building plots
+#[derive(Debug, Clone)]
+pub struct UiPlotParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub title: String,
+  pub capacity: usize,
+  pub y_min: Option<f32>,
+  pub y_max: Option<f32>,
+  pub grid_lines: usize,
+  pub show_labels: bool,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiPlotParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 240.0, 120.0),
+      alignment: UiAlign::TopLeft,
+      title: String::new(),
+      capacity: 120,
+      y_min: None,
+      y_max: None,
+      grid_lines: 4,
+      show_labels: true,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiPlot {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  capacity: usize,
+  pub series: Vec<UiPlotSeries>,
+  pub title: String,
+  pub y_min: Option<f32>,
+  pub y_max: Option<f32>,
+  pub grid_lines: usize,
+  pub show_labels: bool,
+  pub bg_color: Color,
+  pub grid_color: Color,
+  pub text_color: Color,
+  pub palette: Vec<Color>,
+  pub data: Option<UiMetaData>
+}
+impl UiPlot {
+  pub fn new(id: u32, params: UiPlotParams) -> Self {
+    let mut bg_color = Color::new(0.0, 0.0, 0.0, 0.6);
+    let mut grid_color = Color::new(1.0, 1.0, 1.0, 0.15);
+    let mut text_color = WHITE;
+    let mut palette = vec![GREEN, ORANGE, SKYBLUE, PINK];
+    if let Some(tm) = params.theme {
+      bg_color = adjust_alpha(&tm.secondary[0], 0.85);
+      grid_color = adjust_alpha(&contrast_color(&tm.secondary[0]), 0.15);
+      text_color = contrast_color(&tm.secondary[0]);
+      palette = vec![tm.accent[0], tm.success_color, tm.warning_color, tm.info_color];
+    };
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      capacity: params.capacity.max(2),
+      series: Vec::new(),
+      title: params.title,
+      y_min: params.y_min,
+      y_max: params.y_max,
+      grid_lines: params.grid_lines,
+      show_labels: params.show_labels,
+      bg_color,
+      grid_color,
+      text_color,
+      palette,
+      data: None,
+    }
+  }
+  /// adds a series colored from the palette
+  pub fn with_series(mut self, label: &str, style: UiPlotStyle) -> Self {
+    self.add_series(label, style);
+    self
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  /// adds a series colored from the palette, returning its index
+  pub fn add_series(&mut self, label: &str, style: UiPlotStyle) -> usize {
+    let color = self.palette[self.series.len() % self.palette.len()];
+    self.series.push(UiPlotSeries {
+      label: label.to_owned(),
+      color,
+      style,
+      values: UiRingBuffer::new(self.capacity),
+    });
+    self.series.len() - 1
+  }
+  pub fn push(&mut self, series: usize, value: f32) {
+    if let Some(s) = self.series.get_mut(series) {
+      s.values.push(value);
+    }
+  }
+  pub fn clear(&mut self) {
+    for s in self.series.iter_mut() {
+      s.values.clear();
+    }
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Plot(self.clone()));
+    }
+  }
+  /// y axis range and grid step, rounded outwards to readable values
+  fn y_range(&self) -> (f32, f32, f32) {
+    let mut lo = f32::MAX;
+    let mut hi = f32::MIN;
+    for s in self.series.iter() {
+      if let (Some(min), Some(max)) = (s.values.min(), s.values.max()) {
+        lo = lo.min(min);
+        hi = hi.max(max);
+      }
+      // bars grow from zero
+      if s.style == UiPlotStyle::Bar {
+        lo = lo.min(0.0);
+        hi = hi.max(0.0);
+      }
+    }
+    if lo > hi { lo = 0.0; hi = 1.0; }
+    let lo = self.y_min.filter(|v| v.is_finite()).unwrap_or(lo);
+    let hi = self.y_max.filter(|v| v.is_finite()).unwrap_or(hi);
+    // a span too wide for f32 falls back to the unit range
+    let (lo, hi) = if (hi - lo).is_finite() { (lo, hi) } else { (0.0, 1.0) };
+    let divisions = self.grid_lines.clamp(1, MAX_DIVISIONS) as f32;
+    let span = if hi - lo > f32::EPSILON { hi - lo } else { lo.abs().max(1.0) };
+    let step = nice_step(span / divisions);
+    let lo = if self.y_min.is_some() { lo } else { (lo / step).floor() * step };
+    let mut hi = if self.y_max.is_some() { hi } else { (hi / step).ceil() * step };
+    if hi - lo <= f32::EPSILON { hi = lo + step; }
+    // rounding outwards can still overflow right at the edge of f32
+    if !(hi - lo).is_finite() { return (0.0, 1.0, nice_step(1.0 / divisions)); }
+    (lo, hi, step)
+  }
+  pub(crate) fn render(&self, theme: &UiTheme) {
+    let b = self.abs_bounds;
+    draw_rectangle(b.x, b.y, b.w, b.h, self.bg_color);
+    let font_size = (theme.font_size as f32 * 0.75) as u16;
+    let text_params = |color: Color| TextParams {
+      font: theme.font.as_ref(),
+      font_size,
+      color,
+      ..Default::default()
+    };
+    // title and legend
+    let mut top = b.y + PAD;
+    let line_h = measure_text("Ag", theme.font.as_ref(), font_size, 1.0);
+    let has_legend = self.series.len() > 1 || (!self.series.is_empty() && self.title.is_empty());
+    if self.show_labels && (!self.title.is_empty() || has_legend) {
+      let baseline = top + line_h.offset_y;
+      draw_ui_text(&self.title, b.x + PAD, baseline, text_params(self.text_color));
+      if has_legend {
+        let mut x = b.x + b.w - PAD;
+        for s in self.series.iter().rev() {
+          let w = measure_text(&s.label, theme.font.as_ref(), font_size, 1.0).width;
+          x -= w;
+          draw_ui_text(&s.label, x, baseline, text_params(s.color));
+          x -= 10.0;
+        }
+      }
+      top += line_h.height + PAD;
+    }
+    // y axis labels
+    let (lo, hi, step) = self.y_range();
+    let decimals = if step >= 1.0 { 0 } else { (-step.log10().floor()) as usize };
+    let divisions = (((hi - lo) / step).round().max(1.0) as usize).min(MAX_DIVISIONS);
+    let labels: Vec<(f32, String)> = (0..=divisions)
+      .map(|i| {
+        let v = (lo + i as f32 * step).min(hi);
+        (v, format!("{:.*}", decimals, v))
+      })
+      .collect();
+    let gutter = if self.show_labels {
+      labels.iter()
+        .map(|(_, txt)| measure_text(txt, theme.font.as_ref(), font_size, 1.0).width)
+        .fold(0.0, f32::max) + 4.0
+    } else { 0.0 };
+    let area = Rect::new(
+      b.x + PAD + gutter,
+      top,
+      (b.w - 2.0 * PAD - gutter).max(0.0),
+      (b.y + b.h - PAD - top).max(0.0),
+    );
+    if area.w <= 0.0 || area.h <= 0.0 { return; }
+    let to_y = |v: f32| area.y + area.h - (v - lo) / (hi - lo) * area.h;
+    // grid lines
+    for (v, txt) in labels.iter() {
+      let y = to_y(*v);
+      draw_line(area.x, y, area.x + area.w, y, 1.0, self.grid_color);
+      if self.show_labels {
+        let w = measure_text(txt, theme.font.as_ref(), font_size, 1.0).width;
+        let baseline = y - line_h.height / 2.0 + line_h.offset_y;
+        draw_ui_text(txt, area.x - 4.0 - w, baseline, text_params(self.text_color));
+      }
+    }
+    // series, newest value on the right edge
+    push_clip_rect(&area);
+    let slot_w = area.w / self.capacity as f32;
+    for s in self.series.iter() {
+      let offset = self.capacity - s.values.len();
+      match s.style {
+        UiPlotStyle::Bar => {
+          let zero = to_y(0.0_f32.clamp(lo, hi));
+          for (i, v) in s.values.iter().enumerate() {
+            if !v.is_finite() { continue; }
+            let x = area.x + (offset + i) as f32 * slot_w;
+            let y = to_y(*v);
+            let w = (slot_w - 1.0).max(1.0);
+            draw_rectangle(x, y.min(zero), w, (zero - y).abs(), s.color);
+          }
+        }
+        UiPlotStyle::Line => {
+          let step_x = area.w / (self.capacity - 1) as f32;
+          let mut prev: Option<(f32, f32)> = None;
+          for (i, v) in s.values.iter().enumerate() {
+            // gaps break the line instead of shooting off the plot
+            if !v.is_finite() { prev = None; continue; }
+            let pt = (area.x + (offset + i) as f32 * step_x, to_y(*v));
+            if let Some(p) = prev {
+              draw_line(p.0, p.1, pt.0, pt.1, 1.5, s.color);
+            }
+            prev = Some(pt);
+          }
+        }
+      }
+    }
+    pop_clip_rect();
+  }
+}
+
+/// rounds a raw step up to 1, 2 or 5 times a power of ten
+fn nice_step(raw: f32) -> f32 {
+  if raw <= 0.0 || !raw.is_finite() { return 1.0; }
+  let mag = 10f32.powf(raw.log10().floor());
+  let norm = raw / mag;
+  let nice = if norm <= 1.0 { 1.0 }
+    else if norm <= 2.0 { 2.0 }
+    else if norm <= 5.0 { 5.0 }
+    else { 10.0 };
+  nice * mag
+}
@@ -0,0 +1,154 @@
+use crate::mq_ui::*;
+
+/// helper struct for building progress bars
+#[derive(Debug, Clone)]
+pub struct UiProgressParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub value: f32,
+  pub indeterminate: bool,
+  pub show_text: bool,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiProgressParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 200.0, 20.0),
+      alignment: UiAlign::TopLeft,
+      value: 0.0,
+      indeterminate: false,
+      show_text: true,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiProgress {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  value: f32,
+  /// shows moving stripes instead of a fill when the amount of work is unknown
+  pub indeterminate: bool,
+  pub show_text: bool,
+  stripe_offset: f32,
+  pub fill_color: Color,
+  pub track_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiProgress {
+  pub fn new(id: u32, params: UiProgressParams) -> Self {
+    let mut fill_color = BLUE;
+    let mut track_color = GRAY;
+    if let Some(tm) = params.theme {
+      fill_color = tm.accent[0];
+      track_color = tm.secondary[1];
+    };
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      value: params.value.clamp(0.0, 1.0),
+      indeterminate: params.indeterminate,
+      show_text: params.show_text,
+      stripe_offset: 0.0,
+      fill_color,
+      track_color,
+      data: None
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub fn value(&self) -> f32 {
+    self.value
+  }
+  pub fn set_value(&mut self, value: f32) {
+    self.value = value.clamp(0.0, 1.0);
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    // move stripes by one stripe period every half second
+    if self.indeterminate {
+      let period = self.stripe_period();
+      self.stripe_offset = (self.stripe_offset + time_delta * period * 2.0) % period;
+    }
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Progress(self.clone()));
+    }
+  }
+  fn stripe_period(&self) -> f32 {
+    (self.abs_bounds.h * 1.5).max(1.0)
+  }
+  pub(crate) fn render(&self, theme: &UiTheme) {
+    let b = self.abs_bounds;
+    draw_rectangle(b.x, b.y, b.w, b.h, self.track_color);
+    if self.indeterminate {
+      // slanted stripes, clipped to the track
+      let period = self.stripe_period();
+      let stripe_w = period / 2.0;
+      let slant = b.h;
+      push_clip_rect(&b);
+      let mut x = b.x - slant - period + self.stripe_offset;
+      while x < b.x + b.w {
+        let bl = vec2(x, b.y + b.h);
+        let br = vec2(x + stripe_w, b.y + b.h);
+        let tl = vec2(x + slant, b.y);
+        let tr = vec2(x + slant + stripe_w, b.y);
+        draw_triangle(bl, br, tr, self.fill_color);
+        draw_triangle(bl, tr, tl, self.fill_color);
+        x += period;
+      }
+      pop_clip_rect();
+    } else {
+      draw_rectangle(b.x, b.y, b.w * self.value, b.h, self.fill_color);
+      // percentage text
+      if self.show_text {
+        let text = format!("{}%", (self.value * 100.0).round() as i32);
+        let txt_size = measure_text(&text, theme.font.as_ref(), theme.font_size, 1.0);
+        let txt_x = b.x + (b.w - txt_size.width) / 2.0;
+        let txt_y = b.y + txt_size.offset_y + (b.h - txt_size.height) / 2.0;
+        // readable over whichever part sits under the text center
+        let under = if self.value >= 0.5 { self.fill_color } else { self.track_color };
+        draw_ui_text(&text, txt_x, txt_y, TextParams {
+          font: theme.font.as_ref(),
+          font_size: theme.font_size,
+          color: contrast_color(&under),
+          ..Default::default()
+        });
+      }
+    }
+    // draw border
+    draw_rectangle_lines(b.x, b.y, b.w, b.h, 1.5, BLACK);
+  }
+}
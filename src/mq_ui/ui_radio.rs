@@ -25,7 +25,7 @@ pub struct UiRadio {
   pub event: UiAction,
   holding: bool,
   abs_bounds: Rect,
-  rel_bounds: UiRect,
+  pub(crate) rel_bounds: UiRect,
   alignment: UiAlign,
   label: String,
   pub checked: bool,
@@ -35,11 +35,8 @@ pub struct UiRadio {
 impl UiRadio {
   pub fn new(id:u32, params: UiRadioParams) -> Self {
     let mut cir_color = GRAY;
-    match params.theme {
-      Some(tm) => {
-        cir_color = tm.secondary[1];
-      }
-      None => ()
+    if let Some(tm) = params.theme {
+      cir_color = tm.secondary[1];
     };
     Self {
       id,
@@ -58,28 +55,18 @@ impl UiRadio {
     self.data = Some(meta_data);
     self
   }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
   pub(crate) fn update(
     &mut self,
     target: &mut Option<UiElement>,
-    parent_rect: &Rect,
-    parent_delta: &(f32, f32),
-    mouse_pos: &(f32, f32),
-    mouse_delta: &(f32, f32),
-    l_mouse: &UiMouseAction,
-    r_mouse: &UiMouseAction,
+    frame: &UiFrameInput,
   ) {
-    let pos_update = update_position_adv(
-      &self.abs_bounds,
-      &self.rel_bounds,
-      parent_rect,
-      parent_delta,
-      &self.alignment,
-      mouse_delta,
-      false,
-      self.holding,
-    );
-    self.abs_bounds = pos_update.0;
-    self.rel_bounds = pos_update.1;
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
     // update self
     let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
     let mut action_available = target.is_none();
@@ -115,7 +102,7 @@ impl UiRadio {
       let txt_size = measure_text(&self.label, theme.font.as_ref(), theme.font_size, 1.0);
       let txt_x = self.abs_bounds.x + 24.0;
       let txt_y = self.abs_bounds.y - 2.0 + (txt_size.height + self.abs_bounds.h) / 2.0;
-      draw_text_ex(&self.label, txt_x, txt_y, TextParams {
+      draw_ui_text(&self.label, txt_x, txt_y, TextParams {
         font: theme.font.as_ref(),
         font_size: theme.font_size,
         color: contrast_color(parent_color),
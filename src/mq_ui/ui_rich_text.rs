@@ -0,0 +1,236 @@
+use crate::mq_ui::*;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UiTextStyle {
+  pub bold: bool,
+  pub color: Option<Color>,
+  pub size: Option<u16>,
+  pub link: Option<String>,
+}
+
+/// a run of text, or a single inline icon, sharing one style
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UiTextSpan {
+  pub text: String,
+  pub icon: Option<UiTexture>,
+  pub style: UiTextStyle,
+}
+impl UiTextSpan {
+  pub fn plain(text: &str) -> Self {
+    Self { text: text.to_owned(), ..Default::default() }
+  }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum UiMarkupTag {
+  Bold,
+  Color(Color),
+  Size(u16),
+  Link(String),
+}
+impl UiMarkupTag {
+  fn name(&self) -> &str {
+    match self {
+      UiMarkupTag::Bold => "b",
+      UiMarkupTag::Color(_) => "color",
+      UiMarkupTag::Size(_) => "size",
+      UiMarkupTag::Link(_) => "url",
+    }
+  }
+}
+
+/// parses a small bbcode subset into styled spans
+///
+/// supports `[b]`, `[color=#RRGGBB]` (or a color name), `[size=N]`,
+/// `[url=target]` and `[icon=N]` with N a theme texture index.
+/// `[[` escapes an opening bracket, unknown tags are kept as text.
+pub fn parse_markup(src: &str) -> Vec<UiTextSpan> {
+  let mut spans: Vec<UiTextSpan> = Vec::new();
+  let mut stack: Vec<UiMarkupTag> = Vec::new();
+  let mut text = String::new();
+  let style_of = |stack: &Vec<UiMarkupTag>| {
+    let mut style = UiTextStyle::default();
+    for tag in stack.iter() {
+      match tag {
+        UiMarkupTag::Bold => style.bold = true,
+        UiMarkupTag::Color(c) => style.color = Some(*c),
+        UiMarkupTag::Size(s) => style.size = Some(*s),
+        UiMarkupTag::Link(l) => style.link = Some(l.clone()),
+      }
+    }
+    style
+  };
+  let flush = |text: &mut String, spans: &mut Vec<UiTextSpan>, stack: &Vec<UiMarkupTag>| {
+    if text.is_empty() { return; }
+    spans.push(UiTextSpan { text: std::mem::take(text), icon: None, style: style_of(stack) });
+  };
+  let mut rest = src;
+  while let Some(open) = rest.find('[') {
+    text.push_str(&rest[..open]);
+    let after = &rest[open + 1..];
+    if let Some(stripped) = after.strip_prefix('[') {
+      text.push('[');
+      rest = stripped;
+      continue;
+    }
+    let close = match after.find(']') {
+      Some(i) => i,
+      None => {
+        text.push('[');
+        rest = after;
+        continue;
+      }
+    };
+    let tag = &after[..close];
+    let (name, value) = match tag.split_once('=') {
+      Some((n, v)) => (n.trim().to_lowercase(), Some(v.trim())),
+      None => (tag.trim().to_lowercase(), None)
+    };
+    let mut handled = true;
+    if let Some(closing) = name.strip_prefix('/') {
+      match stack.iter().rposition(|t| t.name() == closing) {
+        Some(idx) => {
+          flush(&mut text, &mut spans, &stack);
+          stack.remove(idx);
+        }
+        None => handled = false
+      }
+    } else {
+      let opened = match (name.as_str(), value) {
+        ("b", None) => Some(UiMarkupTag::Bold),
+        ("color", Some(v)) => parse_color(v).map(UiMarkupTag::Color),
+        ("size", Some(v)) => v.parse::<u16>().ok().map(UiMarkupTag::Size),
+        ("url", Some(v)) => Some(UiMarkupTag::Link(v.to_owned())),
+        ("icon", Some(v)) => {
+          match v.parse::<usize>() {
+            Ok(idx) => {
+              flush(&mut text, &mut spans, &stack);
+              spans.push(UiTextSpan { text: String::new(), icon: Some(UiTexture(idx)), style: style_of(&stack) });
+            }
+            Err(_) => handled = false
+          }
+          None
+        }
+        _ => {
+          handled = false;
+          None
+        }
+      };
+      if let Some(tag) = opened {
+        flush(&mut text, &mut spans, &stack);
+        stack.push(tag);
+      } else if name != "icon" {
+        handled = false;
+      }
+    }
+    if handled {
+      rest = &after[close + 1..];
+    } else {
+      text.push('[');
+      rest = after;
+    }
+  }
+  text.push_str(rest);
+  flush(&mut text, &mut spans, &stack);
+  spans
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+  if value.starts_with('#') { return hex_to_color(value); }
+  match value.to_lowercase().as_str() {
+    "white" => Some(WHITE),
+    "black" => Some(BLACK),
+    "gray" | "grey" => Some(GRAY),
+    "red" => Some(RED),
+    "orange" => Some(ORANGE),
+    "yellow" => Some(YELLOW),
+    "green" => Some(GREEN),
+    "blue" => Some(BLUE),
+    "skyblue" => Some(SKYBLUE),
+    "purple" => Some(PURPLE),
+    "pink" => Some(PINK),
+    _ => None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn texts(spans: &[UiTextSpan]) -> Vec<&str> {
+    spans.iter().map(|s| s.text.as_str()).collect()
+  }
+
+  #[test]
+  fn nested_tags_combine_styles() {
+    let spans = parse_markup("[b]a[color=red]b[/color]c[/b]d");
+    assert_eq!(texts(&spans), ["a", "b", "c", "d"]);
+    assert!(spans[0].style.bold && spans[0].style.color.is_none());
+    assert!(spans[1].style.bold && spans[1].style.color == Some(RED));
+    assert!(spans[2].style.bold && spans[2].style.color.is_none());
+    assert_eq!(spans[3].style, UiTextStyle::default());
+  }
+
+  #[test]
+  fn closing_an_outer_tag_keeps_inner_ones() {
+    let spans = parse_markup("[b][size=20]a[/b]b[/size]");
+    assert_eq!(texts(&spans), ["a", "b"]);
+    assert!(spans[0].style.bold && spans[0].style.size == Some(20));
+    assert!(!spans[1].style.bold && spans[1].style.size == Some(20));
+  }
+
+  #[test]
+  fn unknown_tags_stay_as_text() {
+    let spans = parse_markup("[foo]x[/foo] [/b]");
+    assert_eq!(texts(&spans), ["[foo]x[/foo] [/b]"]);
+    assert_eq!(spans[0].style, UiTextStyle::default());
+  }
+
+  #[test]
+  fn unclosed_tags_run_to_the_end() {
+    let spans = parse_markup("a[b]bold");
+    assert_eq!(texts(&spans), ["a", "bold"]);
+    assert!(spans[1].style.bold);
+    // a bracket that is never closed is plain text
+    assert_eq!(texts(&parse_markup("a [b")), ["a [b"]);
+  }
+
+  #[test]
+  fn url_targets_keep_everything_after_the_first_equals() {
+    let spans = parse_markup("see [url=https://example.com/?a=b]docs[/url]");
+    assert_eq!(texts(&spans), ["see ", "docs"]);
+    assert_eq!(spans[1].style.link.as_deref(), Some("https://example.com/?a=b"));
+    assert!(spans[0].style.link.is_none());
+  }
+
+  #[test]
+  fn color_and_size_arguments() {
+    let spans = parse_markup("[color=#FF8000]a[/color][color=Blue]b[/color][size=24]c[/size]");
+    assert_eq!(spans[0].style.color, hex_to_color("#FF8000"));
+    assert!(spans[0].style.color.is_some());
+    assert_eq!(spans[1].style.color, Some(BLUE));
+    assert_eq!(spans[2].style.size, Some(24));
+  }
+
+  #[test]
+  fn invalid_arguments_stay_as_text() {
+    let spans = parse_markup("[color=nope]a[size=big]b[b=1]c");
+    assert_eq!(texts(&spans), ["[color=nope]a[size=big]b[b=1]c"]);
+  }
+
+  #[test]
+  fn double_brackets_are_literal() {
+    let spans = parse_markup("a [[b] c [[[b]d");
+    assert_eq!(texts(&spans), ["a [b] c [", "d"]);
+    assert!(!spans[0].style.bold && spans[1].style.bold);
+  }
+
+  #[test]
+  fn icons_are_their_own_span() {
+    let spans = parse_markup("[b]a[icon=2]b[/b]");
+    assert_eq!(texts(&spans), ["a", "", "b"]);
+    assert_eq!(spans[1].icon, Some(UiTexture(2)));
+    assert!(spans[1].style.bold);
+    assert_eq!(texts(&parse_markup("[icon=x]")), ["[icon=x]"]);
+  }
+}
@@ -1,25 +1,43 @@
+use std::collections::HashMap;
 use macroquad::window;
 use miniquad::window::set_mouse_cursor;
 use miniquad::CursorIcon;
 use crate::mq_ui::*;
+use crate::mq_ui::ui_toast::{TOAST_W, TOAST_H, TOAST_GAP};
 
 #[derive(Debug, Clone)]
 pub struct UiRoot {
   pub theme: UiTheme,
   children: Vec<UiElement>,
+  z_index: HashMap<u32, i32>,
+  toasts: Vec<UiToast>,
+  pub toast_corner: UiAlign,
+  pub max_toasts: usize,
+  scale: Option<f32>,
+  perf_overlay: Option<UiPerfOverlay>,
+  update_ms: f32,
+  render_ms: f32,
   prev_mouse_pos: (f32, f32),
-  prev_screen: Rect,
+  prev_screen: (f32, f32),
+  layout_dirty: bool,
   id_counter: u32,
 }
 impl UiRoot {
   pub fn new() -> Self {
-    let w = window::screen_width();
-    let h = window::screen_height();
     Self {
       theme: UiTheme::default(),
       children: Vec::new(),
+      z_index: HashMap::new(),
+      toasts: Vec::new(),
+      toast_corner: UiAlign::BottomRight,
+      max_toasts: 5,
+      scale: None,
+      perf_overlay: None,
+      update_ms: 0.0,
+      render_ms: 0.0,
       prev_mouse_pos: (0.0, 0.0),
-      prev_screen: Rect::new(0.0, 0.0, w, h),
+      prev_screen: (0.0, 0.0),
+      layout_dirty: true,
       id_counter: 1,
     }
   }
@@ -32,42 +50,81 @@ impl UiRoot {
     self.theme = theme;
     self
   }
+  pub fn with_scale(mut self, scale: f32) -> Self {
+    self.scale = Some(scale);
+    self
+  }
+  /// physical pixels per ui pixel, None follows the window's dpi scale
+  pub fn set_scale(&mut self, scale: Option<f32>) {
+    self.scale = scale;
+  }
+  /// physical pixels per ui pixel, multiplying every px size, font size and border
+  pub fn scale(&self) -> f32 {
+    self.scale.unwrap_or_else(screen_dpi_scale).max(0.1)
+  }
+  /// camera that maps ui pixels onto the whole screen
+  fn camera(&self) -> Camera2D {
+    let scrn = ui_screen_rect();
+    Camera2D {
+      target: vec2(scrn.w / 2.0, scrn.h / 2.0),
+      zoom: vec2(2.0 / scrn.w, 2.0 / scrn.h),
+      ..Default::default()
+    }
+  }
   pub fn update(&mut self) -> Option<UiElement> {
-    if self.children.is_empty() { return None; }
-    // setup transient state
+    // feed last frame's timings to the overlay
+    if let Some(perf) = self.perf_overlay.as_mut() {
+      perf.update(get_frame_time() * 1000.0, self.update_ms, self.render_ms);
+    }
+    if self.children.is_empty() && self.toasts.is_empty() { return None; }
+    let start_time = get_time();
+    set_ui_scale(self.scale());
+    begin_input_frame();
+    // setup transient state, the mouse is converted from physical to ui pixels
     let mut action_target = None;
+    let unit = self.scale() / screen_dpi_scale();
     let mouse_pos = mouse_position();
+    let mouse_pos = (mouse_pos.0 / unit, mouse_pos.1 / unit);
     let mouse_delta = (
       mouse_pos.0 - self.prev_mouse_pos.0,
       mouse_pos.1 - self.prev_mouse_pos.1
     );
     self.prev_mouse_pos = mouse_pos;
-    let w = window::screen_width();
-    let h = window::screen_height();
-    let scrn = Rect::new(0.0, 0.0, w, h);
-    let scrn_delta = (w - self.prev_screen.w, h - self.prev_screen.h);
-    self.prev_screen = scrn;
+    let scrn = ui_screen_rect();
+    if self.prev_screen != (scrn.w, scrn.h) {
+      self.prev_screen = (scrn.w, scrn.h);
+      self.layout_dirty = true;
+    }
+    self.arrange(&scrn);
     let (l_mouse, r_mouse) = get_mouse_actions();
-    let t_delta = get_frame_time();
+    let frame = UiFrameInput {
+      mouse_pos,
+      mouse_delta,
+      l_mouse,
+      r_mouse,
+      time_delta: get_frame_time(),
+    };
+    // update toasts above everything else
+    self.update_toasts(&mut action_target, &scrn, &frame);
     // update children
     update_children(
       &mut self.children,
+      &self.z_index,
       &mut action_target,
       &scrn,
-      &scrn_delta,
-      &mouse_pos,
-      &mouse_delta,
-      &l_mouse,
-      &r_mouse,
-      &t_delta,
+      &frame,
+      &mut self.layout_dirty,
     );
+    // settle anything moved or resized by this update before it is rendered
+    self.arrange(&scrn);
     // update cursor
     let mut cursor_icon = CursorIcon::Default;
-    if action_target.is_some() {
+    if let Some(action_target) = action_target.as_ref() {
       let event;
       let show_hover;
       let mut text_input = false;
-      match action_target.as_ref().unwrap() {
+      let mut custom_cursor = None;
+      match action_target {
         UiElement::Box(e) => {
           event = e.event.clone();
           show_hover = e.show_hover;
@@ -78,7 +135,7 @@ impl UiRoot {
         }
         UiElement::Text(e) => {
           event = e.event.clone();
-          show_hover = false;
+          show_hover = e.link_hovered();
         }
         UiElement::Input(e) => {
           event = e.event.clone();
@@ -89,10 +146,68 @@ impl UiRoot {
           event = e.event.clone();
           show_hover = true;
         }
+        UiElement::Window(e) => {
+          event = e.event.clone();
+          show_hover = false;
+          custom_cursor = e.cursor();
+        }
+        UiElement::Toggle(e) => {
+          event = e.event.clone();
+          show_hover = true;
+        }
+        UiElement::Image(e) => {
+          event = e.event.clone();
+          show_hover = false;
+        }
+        UiElement::NumberInput(e) => {
+          event = e.event.clone();
+          show_hover = true;
+          text_input = e.text_hovered();
+        }
+        UiElement::ColorPicker(e) => {
+          event = e.event.clone();
+          show_hover = true;
+          text_input = e.text_hovered();
+        }
+        UiElement::Toast(e) => {
+          event = e.event.clone();
+          show_hover = true;
+        }
+        UiElement::Canvas(e) => {
+          event = e.event.clone();
+          show_hover = false;
+        }
+        UiElement::Plot(e) => {
+          event = e.event.clone();
+          show_hover = false;
+        }
+        UiElement::Console(e) => {
+          event = e.event.clone();
+          show_hover = false;
+          text_input = e.text_hovered();
+        }
+        UiElement::Collapsible(e) => {
+          event = e.event.clone();
+          show_hover = e.header_hovered();
+        }
+        UiElement::Progress(e) => {
+          event = e.event.clone();
+          show_hover = false;
+        }
+        UiElement::Spinner(e) => {
+          event = e.event.clone();
+          show_hover = false;
+        }
+        UiElement::Split(e) => {
+          event = e.event.clone();
+          show_hover = false;
+          custom_cursor = e.cursor();
+        }
       };
       match event {
         UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease => {
-          if text_input { cursor_icon = CursorIcon::Text; }
+          if let Some(icon) = custom_cursor { cursor_icon = icon; }
+          else if text_input { cursor_icon = CursorIcon::Text; }
           else if show_hover { cursor_icon = CursorIcon::Pointer; }
           else { cursor_icon = CursorIcon::Default; }
         }
@@ -100,18 +215,126 @@ impl UiRoot {
       };
     }
     set_mouse_cursor(cursor_icon);
+    self.update_ms = ((get_time() - start_time) * 1000.0) as f32;
     // surface action target
     action_target
   }
+  /// repositions the tree if anything changed since the last pass
+  fn arrange(&mut self, scrn: &Rect) {
+    if !self.layout_dirty { return; }
+    self.layout_dirty = false;
+    arrange_children(&mut self.children, scrn);
+  }
+  /// forces a layout pass on the next update, for changes made outside of it
+  pub fn mark_layout_dirty(&mut self) {
+    self.layout_dirty = true;
+  }
   pub fn render(&mut self) {
+    let start_time = get_time();
+    set_ui_scale(self.scale());
+    push_camera_state();
+    set_camera(&self.camera());
     render_children(&mut self.children, &self.theme, &WHITE);
+    for toast in self.toasts.iter() {
+      toast.render(&self.theme);
+    }
+    self.render_ms = ((get_time() - start_time) * 1000.0) as f32;
+    // overlay is drawn on top and left out of the measurement
+    if let Some(perf) = self.perf_overlay.as_mut() {
+      perf.render(&self.theme);
+    }
+    pop_camera_state();
+  }
+  pub fn set_perf_overlay(&mut self, overlay: UiPerfOverlay) {
+    self.perf_overlay = Some(overlay);
+  }
+  pub fn perf_overlay_mut(&mut self) -> Option<&mut UiPerfOverlay> {
+    self.perf_overlay.as_mut()
+  }
+  /// time spent in the last update, in milliseconds
+  pub fn update_time(&self) -> f32 {
+    self.update_ms
+  }
+  /// time spent in the last render, in milliseconds
+  pub fn render_time(&self) -> f32 {
+    self.render_ms
+  }
+  /// shows a notification that dismisses itself after `duration` seconds
+  ///
+  /// toast ids come from the internal range, so they never clash with element ids.
+  pub fn toast(&mut self, text: &str, kind: UiToastKind, duration: f32) -> u32 {
+    let id = internal_id();
+    self.toasts.push(UiToast::new(id, text, kind, duration));
+    if self.toasts.len() > self.max_toasts {
+      self.toasts.remove(0);
+    }
+    id
+  }
+  fn update_toasts(
+    &mut self,
+    target: &mut Option<UiElement>,
+    scrn: &Rect,
+    frame: &UiFrameInput,
+  ) {
+    let margin = 10.0;
+    let x = match self.toast_corner {
+      UiAlign::TopLeft | UiAlign::CenterLeft | UiAlign::BottomLeft => margin,
+      UiAlign::TopCenter | UiAlign::FullCenter | UiAlign::BottomCenter => (scrn.w - TOAST_W) / 2.0,
+      _ => scrn.w - TOAST_W - margin
+    };
+    let from_top = matches!(self.toast_corner, UiAlign::TopLeft | UiAlign::TopCenter | UiAlign::TopRight);
+    // newest toast sits closest to the corner
+    for (i, toast) in self.toasts.iter_mut().rev().enumerate() {
+      let offset = margin + i as f32 * (TOAST_H + TOAST_GAP);
+      let y = if from_top { offset } else { scrn.h - offset - TOAST_H };
+      let slot = Rect::new(x, y, TOAST_W, TOAST_H);
+      toast.update(target, &slot, frame);
+    }
+    self.toasts.retain(|t| !t.expired());
   }
   pub fn add_child(&mut self, elem: UiElement) {
     self.children.push(elem);
+    self.layout_dirty = true;
   }
   pub fn find_element(&self, id: u32) -> Option<&UiElement> {
     find_node(&self.children, id)
   }
+  /// the element may be changed in ways that affect layout, so the next
+  /// update arranges the tree again
+  pub fn find_element_mut(&mut self, id: u32) -> Option<&mut UiElement> {
+    self.layout_dirty = true;
+    find_node_mut(&mut self.children, id)
+  }
+  /// for changes that don't move or resize the element, such as values, log
+  /// lines or plot samples, so the tree isn't arranged again
+  ///
+  /// text that changes size relays itself out, anything else that affects
+  /// layout needs `find_element_mut` or `mark_layout_dirty`.
+  pub fn find_element_content_mut(&mut self, id: u32) -> Option<&mut UiElement> {
+    find_node_mut(&mut self.children, id)
+  }
+  /// sets the z-index of any element among its siblings, higher is drawn on top
+  pub fn set_z_index(&mut self, id: u32, z_index: i32) {
+    if let Some((siblings, z)) = find_siblings_mut(&mut self.children, &mut self.z_index, id) {
+      z.insert(id, z_index);
+      sort_by_z_index(siblings, z);
+      self.layout_dirty = true;
+    }
+  }
+  /// draws an element above its siblings of the same z-index
+  pub fn bring_to_front(&mut self, id: u32) {
+    if let Some((siblings, z)) = find_siblings_mut(&mut self.children, &mut self.z_index, id) {
+      restack_child(siblings, z, id, true);
+      self.layout_dirty = true;
+    }
+  }
+  /// draws an element below its siblings of the same z-index
+  pub fn send_to_back(&mut self, id: u32) {
+    if let Some((siblings, z)) = find_siblings_mut(&mut self.children, &mut self.z_index, id) {
+      restack_child(siblings, z, id, false);
+      self.layout_dirty = true;
+    }
+  }
   pub fn new_id(&mut self) -> u32 {
     let id = self.id_counter;
     self.id_counter += 1;
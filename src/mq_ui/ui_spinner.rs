@@ -0,0 +1,112 @@
+use crate::mq_ui::*;
+
+/// helper struct for building busy spinners
+#[derive(Debug, Clone)]
+pub struct UiSpinnerParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub thickness: f32,
+  /// revolutions per second
+  pub speed: f32,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiSpinnerParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 32.0, 32.0),
+      alignment: UiAlign::TopLeft,
+      thickness: 4.0,
+      speed: 1.0,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiSpinner {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  pub thickness: f32,
+  pub speed: f32,
+  pub spinning: bool,
+  elapsed: f32,
+  pub color: Color,
+  pub track_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiSpinner {
+  pub fn new(id: u32, params: UiSpinnerParams) -> Self {
+    let mut color = BLUE;
+    let mut track_color = GRAY;
+    if let Some(tm) = params.theme {
+      color = tm.accent[0];
+      track_color = tm.secondary[1];
+    };
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      thickness: params.thickness,
+      speed: params.speed,
+      spinning: true,
+      elapsed: 0.0,
+      color,
+      track_color,
+      data: None
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    if self.spinning {
+      self.elapsed += time_delta;
+    }
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Spinner(self.clone()));
+    }
+  }
+  pub(crate) fn render(&self) {
+    if !self.spinning { return; }
+    let b = self.abs_bounds;
+    let radius = (b.w.min(b.h) - self.thickness) / 2.0;
+    if radius <= 0.0 { return; }
+    let (x, y) = (b.x + b.w / 2.0, b.y + b.h / 2.0);
+    draw_arc(x, y, 48, radius, 0.0, self.thickness, 360.0, self.track_color);
+    // the arc grows and shrinks while it turns
+    let turn = self.elapsed * self.speed * 360.0;
+    let arc = 150.0 + 110.0 * (self.elapsed * self.speed * std::f32::consts::PI).sin();
+    draw_arc(x, y, 48, radius, turn, self.thickness, arc, self.color);
+  }
+}
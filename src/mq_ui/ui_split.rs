@@ -0,0 +1,272 @@
+use miniquad::CursorIcon;
+use crate::mq_ui::*;
+
+const DOUBLE_CLICK_TIME: f64 = 0.35;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiSplitDir { Horizontal, Vertical }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiSplitPane { First, Second }
+
+/// helper struct for building split panes
+#[derive(Debug, Clone)]
+pub struct UiSplitParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub direction: UiSplitDir,
+  pub ratio: f32,
+  pub divider: f32,
+  pub min_sizes: (f32, f32),
+  pub max_sizes: (f32, f32),
+  pub collapse_pane: UiSplitPane,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiSplitParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      alignment: UiAlign::TopLeft,
+      direction: UiSplitDir::Horizontal,
+      ratio: 0.5,
+      divider: 6.0,
+      min_sizes: (0.0, 0.0),
+      max_sizes: (f32::MAX, f32::MAX),
+      collapse_pane: UiSplitPane::First,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiSplit {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  pub(crate) first: UiBox,
+  pub(crate) second: UiBox,
+  dragging: bool,
+  last_click: f64,
+  collapsed: Option<UiSize>,
+  pub direction: UiSplitDir,
+  pub ratio: UiSize,
+  pub divider: f32,
+  pub min_sizes: (f32, f32),
+  pub max_sizes: (f32, f32),
+  pub collapse_pane: UiSplitPane,
+  pub color: Color,
+  pub hover_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiSplit {
+  pub fn new(id: u32, params: UiSplitParams) -> Self {
+    let mut color = DARKGRAY;
+    let mut hover_color = GRAY;
+    if let Some(tm) = params.theme {
+      color = tm.secondary[2];
+      hover_color = tm.secondary[3];
+    };
+    let pane_params = UiBoxParams {
+      pos_size: UiRect::from_percent(0.0, 0.0, 1.0, 1.0),
+      theme: params.theme,
+      ..Default::default()
+    };
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      first: UiBox::new(internal_id(), pane_params.clone()),
+      second: UiBox::new(internal_id(), pane_params),
+      dragging: false,
+      last_click: -1.0,
+      collapsed: None,
+      direction: params.direction,
+      ratio: UiSize::Percent(params.ratio.clamp(0.0, 1.0)),
+      divider: params.divider,
+      min_sizes: params.min_sizes,
+      max_sizes: params.max_sizes,
+      collapse_pane: params.collapse_pane,
+      color,
+      hover_color,
+      data: None,
+    }
+  }
+  pub fn with<F>(mut self, func: F) -> Self
+  where F: Fn(&mut UiBox, &mut UiBox) {
+    func(&mut self.first, &mut self.second);
+    self
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn first_pane(&mut self) -> &mut UiBox {
+    &mut self.first
+  }
+  pub fn second_pane(&mut self) -> &mut UiBox {
+    &mut self.second
+  }
+  pub fn is_collapsed(&self) -> bool {
+    self.collapsed.is_some()
+  }
+  /// collapses the configured pane, or restores the previous ratio
+  pub fn toggle_collapse(&mut self) {
+    match self.collapsed.take() {
+      Some(ratio) => {
+        self.ratio = ratio;
+      }
+      None => {
+        self.collapsed = Some(self.ratio);
+        self.ratio = match self.collapse_pane {
+          UiSplitPane::First => UiSize::Percent(0.0),
+          UiSplitPane::Second => UiSize::Percent(1.0),
+        };
+      }
+    }
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    let (first_rect, second_rect) = self.pane_rects();
+    self.first.arrange(&first_rect);
+    self.second.arrange(&second_rect);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
+  ) {
+    let UiFrameInput { mouse_pos, mouse_delta, l_mouse, r_mouse, .. } = frame;
+    // divider takes priority over pane content
+    let on_divider = self.dragging || point_in_rect(mouse_pos, &self.divider_hit_rect());
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      on_divider,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    if self.event == UiAction::LClick {
+      let now = get_time();
+      if now - self.last_click < DOUBLE_CLICK_TIME {
+        self.toggle_collapse();
+        *layout_dirty = true;
+        self.last_click = -1.0;
+      } else {
+        self.last_click = now;
+        self.dragging = true;
+      }
+    }
+    if !self.holding { self.dragging = false; }
+    // drag divider
+    if self.dragging && mouse_delta != &(0.0, 0.0) {
+      let (start, avail) = match self.direction {
+        UiSplitDir::Horizontal => (self.abs_bounds.x, self.abs_bounds.w - self.divider),
+        UiSplitDir::Vertical => (self.abs_bounds.y, self.abs_bounds.h - self.divider),
+      };
+      let pos = match self.direction {
+        UiSplitDir::Horizontal => mouse_pos.0,
+        UiSplitDir::Vertical => mouse_pos.1,
+      };
+      if avail > 0.0 {
+        self.collapsed = None;
+        let first_len = self.clamp_first(pos - start - self.divider / 2.0, avail);
+        self.ratio = UiSize::Percent(first_len / avail);
+        *layout_dirty = true;
+      }
+    }
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Split(Box::new(self.clone())));
+    }
+    // update panes
+    let (first_rect, second_rect) = self.pane_rects();
+    self.first.update(target, &first_rect, frame, layout_dirty);
+    self.second.update(target, &second_rect, frame, layout_dirty);
+  }
+  fn divider_active(&self) -> bool {
+    self.dragging || matches!(self.event, UiAction::Hover | UiAction::Hold | UiAction::LClick | UiAction::LRelease)
+  }
+  pub(crate) fn cursor(&self) -> Option<CursorIcon> {
+    if !self.divider_active() { return None; }
+    match self.direction {
+      UiSplitDir::Horizontal => Some(CursorIcon::EWResize),
+      UiSplitDir::Vertical => Some(CursorIcon::NSResize),
+    }
+  }
+  /// clamps the first pane length so both panes respect their min/max sizes
+  fn clamp_first(&self, first_len: f32, avail: f32) -> f32 {
+    let first_len = first_len.max(self.min_sizes.0).min(self.max_sizes.0);
+    let second_len = (avail - first_len).max(self.min_sizes.1).min(self.max_sizes.1);
+    (avail - second_len).clamp(0.0, avail)
+  }
+  fn first_len(&self) -> (f32, f32) {
+    let avail = match self.direction {
+      UiSplitDir::Horizontal => self.abs_bounds.w - self.divider,
+      UiSplitDir::Vertical => self.abs_bounds.h - self.divider,
+    }.max(0.0);
+    let first_len = self.ratio.resolve(avail, 0.0);
+    if self.collapsed.is_some() { (first_len, avail) }
+    else { (self.clamp_first(first_len, avail), avail) }
+  }
+  fn pane_rects(&self) -> (Rect, Rect) {
+    let b = self.abs_bounds;
+    let (first_len, avail) = self.first_len();
+    let second_len = avail - first_len;
+    match self.direction {
+      UiSplitDir::Horizontal => (
+        Rect::new(b.x, b.y, first_len, b.h),
+        Rect::new(b.x + first_len + self.divider, b.y, second_len, b.h),
+      ),
+      UiSplitDir::Vertical => (
+        Rect::new(b.x, b.y, b.w, first_len),
+        Rect::new(b.x, b.y + first_len + self.divider, b.w, second_len),
+      ),
+    }
+  }
+  fn divider_rect(&self) -> Rect {
+    let b = self.abs_bounds;
+    let (first_len, _) = self.first_len();
+    match self.direction {
+      UiSplitDir::Horizontal => Rect::new(b.x + first_len, b.y, self.divider, b.h),
+      UiSplitDir::Vertical => Rect::new(b.x, b.y + first_len, b.w, self.divider),
+    }
+  }
+  fn divider_hit_rect(&self) -> Rect {
+    let d = self.divider_rect();
+    match self.direction {
+      UiSplitDir::Horizontal => Rect::new(d.x - 3.0, d.y, d.w + 6.0, d.h),
+      UiSplitDir::Vertical => Rect::new(d.x, d.y - 3.0, d.w, d.h + 6.0),
+    }
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    let (first_rect, second_rect) = self.pane_rects();
+    if first_rect.w > 0.0 && first_rect.h > 0.0 { self.first.render(theme); }
+    if second_rect.w > 0.0 && second_rect.h > 0.0 { self.second.render(theme); }
+    // draw divider
+    let d = self.divider_rect();
+    let color = if self.divider_active() { self.hover_color } else { self.color };
+    draw_rectangle(d.x, d.y, d.w, d.h, color);
+    // draw grip
+    let center = (d.x + d.w / 2.0, d.y + d.h / 2.0);
+    for i in -1..=1 {
+      let offset = i as f32 * 6.0;
+      let (x, y) = match self.direction {
+        UiSplitDir::Horizontal => (center.0, center.1 + offset),
+        UiSplitDir::Vertical => (center.0 + offset, center.1),
+      };
+      draw_poly(x, y, 8, 1.5, 0.0, contrast_color(&color));
+    }
+  }
+}
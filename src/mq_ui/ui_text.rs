@@ -7,43 +7,89 @@ pub struct UiTextParams<'a> {
   pub alignment: UiAlign,
   pub text: String,
   pub font_size: u16,
+  pub text_align: UiTextAlign,
+  pub vertical_align: UiTextVAlign,
+  pub line_spacing: f32,
+  pub wrap: bool,
+  pub ellipsis: bool,
+  pub markup: bool,
   pub draggable: bool,
+  pub drag: UiDrag,
   pub theme: Option<&'a UiTheme>,
 }
 impl Default for UiTextParams<'_> {
   fn default() -> Self {
     Self {
-      pos_size: UiRect::from_px(0.0, 0.0, 10.0, 10.0),
+      pos_size: UiRect::from_px(0.0, 0.0, 150.0, 24.0),
       alignment: UiAlign::TopLeft,
       text: "[Display Text]".to_owned(),
       font_size: 18,
+      text_align: UiTextAlign::Left,
+      vertical_align: UiTextVAlign::Top,
+      line_spacing: 1.2,
+      wrap: false,
+      ellipsis: false,
+      markup: false,
       draggable: false,
+      drag: UiDrag::default(),
       theme: None,
     }
   }
 }
 
+/// everything the cached layout depends on, fonts are compared by address
+#[derive(Debug, PartialEq, Clone)]
+struct UiTextLayoutKey {
+  text: String,
+  bounds: Rect,
+  font_size: u16,
+  text_align: UiTextAlign,
+  vertical_align: UiTextVAlign,
+  line_spacing: f32,
+  wrap: bool,
+  ellipsis: bool,
+  markup: bool,
+  link_color: Color,
+  fonts: (Option<usize>, Option<usize>),
+}
+
 #[derive(Debug, Clone)]
 pub struct UiText {
   pub id: u32,
   pub event: UiAction,
   holding: bool,
   abs_bounds: Rect,
-  rel_bounds: UiRect,
+  pub(crate) rel_bounds: UiRect,
   alignment: UiAlign,
   draggable: bool,
+  pub drag: UiDrag,
+  pub drag_event: UiDragEvent,
+  drag_state: UiDragState,
+  layout: UiTextLayout,
+  layout_key: Option<UiTextLayoutKey>,
+  pub(crate) content_size: (f32, f32),
+  relayout: bool,
+  spans: Vec<UiTextSpan>,
+  hovered_link: Option<String>,
   pub text: String,
   pub font_size: u16,
+  pub text_align: UiTextAlign,
+  pub vertical_align: UiTextVAlign,
+  pub line_spacing: f32,
+  pub wrap: bool,
+  pub ellipsis: bool,
+  pub markup: bool,
+  pub link_color: Color,
+  pub link_clicked: Option<String>,
   pub data: Option<UiMetaData>
 }
 impl UiText {
   pub fn new(id: u32, params: UiTextParams) -> Self {
     let mut font_size = params.font_size;
-    match params.theme {
-      Some(tm) => {
-        font_size = tm.font_size;
-      }
-      None => ()
+    let mut link_color = BLUE;
+    if let Some(tm) = params.theme {
+      font_size = tm.font_size;
+      link_color = tm.info_color;
     };
     Self {
       id,
@@ -53,8 +99,25 @@ impl UiText {
       abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
       alignment: params.alignment,
       draggable: params.draggable,
+      drag: params.drag,
+      drag_event: UiDragEvent::None,
+      drag_state: UiDragState::default(),
+      layout: UiTextLayout::default(),
+      layout_key: None,
+      content_size: (0.0, 0.0),
+      relayout: false,
+      spans: Vec::new(),
+      hovered_link: None,
       text: params.text,
       font_size,
+      text_align: params.text_align,
+      vertical_align: params.vertical_align,
+      line_spacing: params.line_spacing,
+      wrap: params.wrap,
+      ellipsis: params.ellipsis,
+      markup: params.markup,
+      link_color,
+      link_clicked: None,
       data: None,
     }
   }
@@ -62,28 +125,37 @@ impl UiText {
     self.data = Some(meta_data);
     self
   }
+  /// glyph positions from the last render
+  pub fn layout(&self) -> &UiTextLayout {
+    &self.layout
+  }
+  /// true while the mouse is over a link span
+  pub fn link_hovered(&self) -> bool {
+    self.hovered_link.is_some()
+  }
+  /// forces the layout to be rebuilt on the next render
+  pub fn invalidate_layout(&mut self) {
+    self.layout_key = None;
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &self.content_size, parent_rect, &self.alignment);
+  }
   pub(crate) fn update(
     &mut self,
     target: &mut Option<UiElement>,
     parent_rect: &Rect,
-    parent_delta: &(f32, f32),
-    mouse_pos: &(f32, f32),
-    mouse_delta: &(f32, f32),
-    l_mouse: &UiMouseAction,
-    r_mouse: &UiMouseAction,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
   ) {
-    let pos_update = update_position_adv(
-      &self.abs_bounds,
-      &self.rel_bounds,
-      parent_rect,
-      parent_delta,
-      &self.alignment,
-      mouse_delta,
-      self.draggable,
-      self.holding,
-    );
-    self.abs_bounds = pos_update.0;
-    self.rel_bounds = pos_update.1;
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    // content measured in the last render changed size
+    if self.relayout {
+      self.relayout = false;
+      *layout_dirty = true;
+    }
     // update self
     let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
     let mut action_available = target.is_none();
@@ -95,19 +167,95 @@ impl UiText {
       l_mouse,
       r_mouse,
     );
+    // find link under the mouse from the last layout
+    self.hovered_link = None;
+    if !action_available {
+      if let Some(glyph) = self.layout.glyph_at(mouse_pos) {
+        self.hovered_link = self.spans.get(glyph.span).and_then(|s| s.style.link.clone());
+      }
+    }
+    self.link_clicked = None;
+    if self.event == UiAction::LRelease {
+      self.link_clicked = self.hovered_link.clone();
+    }
+    // follow the mouse while held
+    self.drag_event = UiDragEvent::None;
+    if self.draggable {
+      self.drag_event = self.drag_state.update(
+        &self.drag,
+        self.holding,
+        &mut self.abs_bounds,
+        parent_rect,
+        mouse_pos,
+      );
+      if let UiDragEvent::Move(..) = self.drag_event {
+        self.rel_bounds = anchor_offset(&self.abs_bounds, parent_rect, &self.alignment, &self.rel_bounds);
+        *layout_dirty = true;
+      }
+    }
     // clone self into target
     if !action_available && target.is_none() {
       target.replace(UiElement::Text(self.clone()));
     }
+    // drag events are surfaced even if something else is hovered
+    if self.drag_event != UiDragEvent::None {
+      target.replace(UiElement::Text(self.clone()));
+    }
   }
-  pub(crate) fn render(&self, theme: &UiTheme, parent_color: &Color) {
-    let txt_size = measure_text(&self.text, theme.font.as_ref(), theme.font_size, 1.0);
-    let txt_y = self.abs_bounds.y + txt_size.height / 2.0;
-    draw_text_ex(&self.text, self.abs_bounds.x, txt_y, TextParams {
-      font: theme.font.as_ref(),
+  pub(crate) fn render(&mut self, theme: &UiTheme, parent_color: &Color) {
+    // only rebuild the layout when something it depends on changes
+    let font_addr = |font: Option<&Font>| font.map(|f| f as *const Font as usize);
+    let key = UiTextLayoutKey {
+      text: self.text.clone(),
+      bounds: self.abs_bounds,
       font_size: self.font_size,
-      color: contrast_color(parent_color),
-      ..Default::default()
-    });
+      text_align: self.text_align,
+      vertical_align: self.vertical_align,
+      line_spacing: self.line_spacing,
+      wrap: self.wrap,
+      ellipsis: self.ellipsis,
+      markup: self.markup,
+      link_color: self.link_color,
+      fonts: (font_addr(theme.font.as_ref()), font_addr(theme.bold_font.as_ref())),
+    };
+    if self.layout_key.as_ref() != Some(&key) {
+      self.spans = if self.markup { parse_markup(&self.text) } else { vec![UiTextSpan::plain(&self.text)] };
+      for span in self.spans.iter_mut() {
+        if span.style.link.is_some() && span.style.color.is_none() {
+          span.style.color = Some(self.link_color);
+        }
+      }
+      let params = UiTextLayoutParams {
+        font: theme.font.as_ref(),
+        bold_font: theme.bold_font.as_ref(),
+        font_size: self.font_size,
+        bounds: self.abs_bounds,
+        align: self.text_align,
+        vertical_align: self.vertical_align,
+        line_spacing: self.line_spacing,
+        wrap: self.wrap,
+        ellipsis: self.ellipsis,
+      };
+      self.layout = UiTextLayout::from_spans(&self.spans, &params);
+      // auto widths fit the text on one line
+      let natural_w = if self.rel_bounds.w == UiSize::Auto {
+        UiTextLayout::from_spans(&self.spans, &UiTextLayoutParams { wrap: false, ellipsis: false, ..params }).size.0
+      } else { self.layout.size.0 };
+      let content_size = (natural_w.ceil(), self.layout.size.1.ceil());
+      if content_size != self.content_size {
+        self.content_size = content_size;
+        self.relayout = true;
+      }
+      self.layout_key = Some(key);
+    }
+    self.layout.draw(theme, contrast_color(parent_color));
+    // underline links
+    for run in self.layout.runs.iter() {
+      let link = self.spans.get(run.span).and_then(|s| s.style.link.as_ref());
+      if link.is_some() && run.icon.is_none() {
+        let color = run.color.unwrap_or(self.link_color);
+        draw_line(run.x, run.baseline + 2.0, run.x + run.width, run.baseline + 2.0, 1.0, color);
+      }
+    }
   }
 }
\ No newline at end of file
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+
+use crate::mq_ui::*;
+
+const ELLIPSIS: &str = "...";
+// stands in for inline icons in glyph lists
+const ICON_CHAR: char = '\u{FFFC}';
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiTextAlign { Left, Center, Right, Justify }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiTextVAlign { Top, Middle, Bottom }
+
+/// helper struct for laying out text
+#[derive(Debug, Clone)]
+pub struct UiTextLayoutParams<'a> {
+  pub font: Option<&'a Font>,
+  pub bold_font: Option<&'a Font>,
+  pub font_size: u16,
+  pub bounds: Rect,
+  pub align: UiTextAlign,
+  pub vertical_align: UiTextVAlign,
+  pub line_spacing: f32,
+  pub wrap: bool,
+  pub ellipsis: bool,
+}
+impl Default for UiTextLayoutParams<'_> {
+  fn default() -> Self {
+    Self {
+      font: None,
+      bold_font: None,
+      font_size: 18,
+      bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      align: UiTextAlign::Left,
+      vertical_align: UiTextVAlign::Top,
+      line_spacing: 1.0,
+      wrap: true,
+      ellipsis: false,
+    }
+  }
+}
+
+/// a positioned character, `index` is the char index in the displayed text
+#[derive(Debug, Clone, Copy)]
+pub struct UiGlyph {
+  pub ch: char,
+  pub index: usize,
+  pub line: usize,
+  pub span: usize,
+  pub rect: Rect,
+}
+
+/// a piece of text or an icon drawn in one call, positioned at its baseline
+#[derive(Debug, Clone)]
+pub struct UiTextRun {
+  pub text: String,
+  pub icon: Option<UiTexture>,
+  pub span: usize,
+  pub x: f32,
+  pub baseline: f32,
+  pub width: f32,
+  pub font_size: u16,
+  pub bold: bool,
+  pub color: Option<Color>,
+}
+
+#[derive(Debug, Clone)]
+struct UiPiece {
+  span: usize,
+  start: usize,
+  text: String,
+  icon: Option<UiTexture>,
+  size: u16,
+  bold: bool,
+  color: Option<Color>,
+  width: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct UiWord {
+  pieces: Vec<UiPiece>,
+  width: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct UiLine {
+  words: Vec<UiWord>,
+  // last line of a paragraph is never justified
+  paragraph_end: bool,
+}
+
+/// caches glyph advances and line metrics per font variant and size
+struct UiTextMeasure<'a> {
+  font: Option<&'a Font>,
+  bold_font: Option<&'a Font>,
+  advances: HashMap<(char, u16, bool), f32>,
+  metrics: HashMap<(u16, bool), TextDimensions>,
+}
+impl<'a> UiTextMeasure<'a> {
+  fn font(&self, bold: bool) -> Option<&'a Font> {
+    if bold { self.bold_font.or(self.font) } else { self.font }
+  }
+  fn metrics(&mut self, size: u16, bold: bool) -> TextDimensions {
+    let font = self.font(bold);
+    *self.metrics.entry((size, bold)).or_insert_with(|| measure_text("Ag", font, size, 1.0))
+  }
+  fn advance(&mut self, c: char, size: u16, bold: bool) -> f32 {
+    let font = self.font(bold);
+    *self.advances.entry((c, size, bold)).or_insert_with(|| {
+      measure_text(&c.to_string(), font, size, 1.0).width
+    })
+  }
+  fn piece_width(&mut self, piece: &UiPiece) -> f32 {
+    match piece.icon {
+      Some(_) => self.metrics(piece.size, piece.bold).height,
+      None => piece.text.chars().map(|c| self.advance(c, piece.size, piece.bold)).sum()
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UiTextLayout {
+  pub runs: Vec<UiTextRun>,
+  pub glyphs: Vec<UiGlyph>,
+  pub line_count: usize,
+  pub size: (f32, f32),
+  pub truncated: bool,
+}
+impl UiTextLayout {
+  pub fn new(text: &str, params: &UiTextLayoutParams) -> Self {
+    Self::from_spans(&[UiTextSpan::plain(text)], params)
+  }
+  pub fn from_spans(spans: &[UiTextSpan], params: &UiTextLayoutParams) -> Self {
+    let mut measure = UiTextMeasure {
+      font: params.font,
+      bold_font: params.bold_font,
+      advances: HashMap::new(),
+      metrics: HashMap::new(),
+    };
+    let max_w = params.bounds.w.max(0.0);
+    let space_w = measure.advance(' ', params.font_size, false);
+    // break into lines
+    let mut lines: Vec<UiLine> = Vec::new();
+    let mut line = UiLine::default();
+    let mut line_w = 0.0;
+    let mut word = UiWord::default();
+    let mut char_idx = 0;
+    let finish_word = |word: &mut UiWord, line: &mut UiLine, lines: &mut Vec<UiLine>, line_w: &mut f32, measure: &mut UiTextMeasure| {
+      if word.pieces.is_empty() { return; }
+      let word = std::mem::take(word);
+      let gap = if line.words.is_empty() { 0.0 } else { space_w };
+      if params.wrap && !line.words.is_empty() && *line_w + gap + word.width > max_w {
+        lines.push(std::mem::take(line));
+        *line_w = 0.0;
+      }
+      // split words that are wider than the whole line
+      let chunks = if params.wrap && word.width > max_w { split_word(word, max_w, measure) } else { vec![word] };
+      let count = chunks.len();
+      for (i, chunk) in chunks.into_iter().enumerate() {
+        let gap = if line.words.is_empty() { 0.0 } else { space_w };
+        *line_w += gap + chunk.width;
+        line.words.push(chunk);
+        if i + 1 < count {
+          lines.push(std::mem::take(line));
+          *line_w = 0.0;
+        }
+      }
+    };
+    for (span_idx, span) in spans.iter().enumerate() {
+      let size = span.style.size.unwrap_or(params.font_size);
+      let bold = span.style.bold;
+      let new_piece = |start: usize| UiPiece {
+        span: span_idx,
+        start,
+        text: String::new(),
+        icon: None,
+        size,
+        bold,
+        color: span.style.color,
+        width: 0.0,
+      };
+      if let Some(icon) = span.icon {
+        let mut piece = new_piece(char_idx);
+        piece.icon = Some(icon);
+        piece.width = measure.piece_width(&piece);
+        word.width += piece.width;
+        word.pieces.push(piece);
+        char_idx += 1;
+        continue;
+      }
+      let mut piece = new_piece(char_idx);
+      for c in span.text.chars() {
+        if c == ' ' || c == '\n' {
+          if !piece.text.is_empty() {
+            word.width += piece.width;
+            word.pieces.push(piece);
+          }
+          finish_word(&mut word, &mut line, &mut lines, &mut line_w, &mut measure);
+          if c == '\n' {
+            line.paragraph_end = true;
+            lines.push(std::mem::take(&mut line));
+            line_w = 0.0;
+          }
+          char_idx += 1;
+          piece = new_piece(char_idx);
+          continue;
+        }
+        piece.width += measure.advance(c, size, bold);
+        piece.text.push(c);
+        char_idx += 1;
+      }
+      if !piece.text.is_empty() {
+        word.width += piece.width;
+        word.pieces.push(piece);
+      }
+    }
+    finish_word(&mut word, &mut line, &mut lines, &mut line_w, &mut measure);
+    line.paragraph_end = true;
+    lines.push(line);
+    // line heights follow the largest piece on each line
+    let line_heights = |lines: &Vec<UiLine>, measure: &mut UiTextMeasure| -> Vec<(f32, f32)> {
+      lines.iter().map(|l| {
+        let (size, bold) = l.words.iter()
+          .flat_map(|w| w.pieces.iter())
+          .map(|p| (p.size, p.bold))
+          .max_by_key(|(size, _)| *size)
+          .unwrap_or((params.font_size, false));
+        let m = measure.metrics(size, bold);
+        let pitch = size as f32 * params.line_spacing.max(0.1);
+        // center the glyph box within the line pitch
+        (pitch, m.offset_y + (pitch - m.height) / 2.0)
+      }).collect()
+    };
+    // truncate overflowing text
+    let mut truncated = false;
+    if params.ellipsis {
+      let heights = line_heights(&lines, &mut measure);
+      let mut used = 0.0;
+      let mut max_lines = 0;
+      for (h, _) in heights.iter() {
+        if used + h > params.bounds.h + 0.5 { break; }
+        used += h;
+        max_lines += 1;
+      }
+      let max_lines = max_lines.max(1);
+      if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        truncated = true;
+      }
+      let last = lines.last_mut().unwrap();
+      if truncated || line_width(last, space_w) > max_w {
+        truncated = true;
+        last.paragraph_end = true;
+        let dots_w = ELLIPSIS.chars().map(|c| measure.advance(c, params.font_size, false)).sum::<f32>();
+        while !last.words.is_empty() && line_width(last, space_w) + dots_w > max_w {
+          let word = last.words.last_mut().unwrap();
+          let piece = word.pieces.last_mut().unwrap();
+          let removed = match piece.icon {
+            Some(_) => {
+              let w = piece.width;
+              piece.icon = None;
+              w
+            }
+            None => match piece.text.pop() {
+              Some(c) => measure.advance(c, piece.size, piece.bold),
+              None => 0.0
+            }
+          };
+          piece.width -= removed;
+          word.width -= removed;
+          if piece.icon.is_none() && piece.text.is_empty() { word.pieces.pop(); }
+          if word.pieces.is_empty() { last.words.pop(); }
+        }
+        let (span, start) = match last.words.last().and_then(|w| w.pieces.last()) {
+          Some(p) => (p.span, p.start + p.text.chars().count()),
+          None => (0, 0)
+        };
+        let dots = UiPiece {
+          span,
+          start,
+          text: ELLIPSIS.to_owned(),
+          icon: None,
+          size: params.font_size,
+          bold: false,
+          color: None,
+          width: dots_w,
+        };
+        match last.words.last_mut() {
+          Some(word) => {
+            word.width += dots_w;
+            word.pieces.push(dots);
+          }
+          None => last.words.push(UiWord { pieces: vec![dots], width: dots_w })
+        }
+      }
+    }
+    // position lines
+    let heights = line_heights(&lines, &mut measure);
+    let total_h: f32 = heights.iter().map(|(h, _)| h).sum();
+    let mut line_top = match params.vertical_align {
+      UiTextVAlign::Top => params.bounds.y,
+      UiTextVAlign::Middle => params.bounds.y + (params.bounds.h - total_h) / 2.0,
+      UiTextVAlign::Bottom => params.bounds.y + params.bounds.h - total_h,
+    };
+    let mut runs = Vec::new();
+    let mut glyphs = Vec::new();
+    let mut widest: f32 = 0.0;
+    for (n, (line, (line_h, ascent))) in lines.iter().zip(heights.iter()).enumerate() {
+      let baseline = line_top + ascent;
+      let width = line_width(line, space_w);
+      widest = widest.max(width);
+      let justify = params.align == UiTextAlign::Justify && !line.paragraph_end && line.words.len() > 1;
+      let x = match params.align {
+        UiTextAlign::Left | UiTextAlign::Justify => params.bounds.x,
+        UiTextAlign::Center => params.bounds.x + (max_w - width) / 2.0,
+        UiTextAlign::Right => params.bounds.x + max_w - width,
+      };
+      let gap = if justify {
+        space_w + (max_w - width) / (line.words.len() - 1) as f32
+      } else { space_w };
+      let mut cursor = x;
+      for (i, word) in line.words.iter().enumerate() {
+        if i > 0 {
+          let first = &word.pieces[0];
+          glyphs.push(UiGlyph {
+            ch: ' ',
+            index: first.start.saturating_sub(1),
+            line: n,
+            span: first.span,
+            rect: Rect::new(cursor, line_top, gap, *line_h),
+          });
+          cursor += gap;
+        }
+        for piece in word.pieces.iter() {
+          runs.push(UiTextRun {
+            text: piece.text.clone(),
+            icon: piece.icon,
+            span: piece.span,
+            x: cursor,
+            baseline,
+            width: piece.width,
+            font_size: piece.size,
+            bold: piece.bold,
+            color: piece.color,
+          });
+          if piece.icon.is_some() {
+            glyphs.push(UiGlyph {
+              ch: ICON_CHAR,
+              index: piece.start,
+              line: n,
+              span: piece.span,
+              rect: Rect::new(cursor, line_top, piece.width, *line_h),
+            });
+            cursor += piece.width;
+            continue;
+          }
+          for (j, c) in piece.text.chars().enumerate() {
+            let w = measure.advance(c, piece.size, piece.bold);
+            glyphs.push(UiGlyph {
+              ch: c,
+              index: piece.start + j,
+              line: n,
+              span: piece.span,
+              rect: Rect::new(cursor, line_top, w, *line_h),
+            });
+            cursor += w;
+          }
+        }
+      }
+      line_top += line_h;
+    }
+    Self {
+      runs,
+      glyphs,
+      line_count: lines.len(),
+      size: (widest, total_h),
+      truncated,
+    }
+  }
+  pub fn draw(&self, theme: &UiTheme, color: Color) {
+    for run in self.runs.iter() {
+      let run_color = run.color.unwrap_or(color);
+      match run.icon {
+        Some(handle) => {
+          // icons sit on the baseline and match the cap height
+          let side = run.width;
+          let m = measure_text("Ag", theme.font.as_ref(), run.font_size, 1.0);
+          let bounds = Rect::new(run.x, run.baseline - m.offset_y, side, side);
+          draw_texture_fit(theme, handle, &bounds, None, &UiImageFit::Contain, run_color);
+        }
+        None => {
+          let font = if run.bold { theme.bold_font.as_ref().or(theme.font.as_ref()) } else { theme.font.as_ref() };
+          draw_ui_text(&run.text, run.x, run.baseline, TextParams {
+            font,
+            font_size: run.font_size,
+            color: run_color,
+            ..Default::default()
+          });
+        }
+      }
+    }
+  }
+  /// glyph under a point, if any
+  pub fn glyph_at(&self, point: &(f32, f32)) -> Option<&UiGlyph> {
+    self.glyphs.iter().find(|g| point_in_rect(point, &g.rect))
+  }
+}
+
+fn line_width(line: &UiLine, space_w: f32) -> f32 {
+  let words: f32 = line.words.iter().map(|w| w.width).sum();
+  words + space_w * line.words.len().saturating_sub(1) as f32
+}
+
+/// breaks a word into chunks no wider than max_w, keeping at least one char each
+fn split_word(word: UiWord, max_w: f32, measure: &mut UiTextMeasure) -> Vec<UiWord> {
+  let mut chunks = Vec::new();
+  let mut chunk = UiWord::default();
+  for piece in word.pieces {
+    let mut part = UiPiece { text: String::new(), width: 0.0, ..piece.clone() };
+    let items: Vec<Option<char>> = match piece.icon {
+      Some(_) => vec![None],
+      None => piece.text.chars().map(Some).collect()
+    };
+    for (i, item) in items.into_iter().enumerate() {
+      let w = match item {
+        Some(c) => measure.advance(c, piece.size, piece.bold),
+        None => piece.width
+      };
+      if chunk.width + part.width + w > max_w && (chunk.width + part.width) > 0.0 {
+        if !part.text.is_empty() || (part.icon.is_some() && part.width > 0.0) {
+          chunk.width += part.width;
+          chunk.pieces.push(part.clone());
+        }
+        chunks.push(std::mem::take(&mut chunk));
+        part = UiPiece { start: piece.start + i, text: String::new(), width: 0.0, ..piece.clone() };
+      }
+      if let Some(c) = item { part.text.push(c); }
+      part.width += w;
+    }
+    if !part.text.is_empty() || part.icon.is_some() {
+      chunk.width += part.width;
+      chunk.pieces.push(part);
+    }
+  }
+  if !chunk.pieces.is_empty() { chunks.push(chunk); }
+  chunks
+}
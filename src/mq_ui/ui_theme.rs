@@ -1,13 +1,23 @@
 use macroquad::prelude::*;
 
+/// handle to a texture registered with a theme
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiTexture(pub usize);
+
 #[derive(Debug, Clone)]
 pub struct UiTheme {
   pub font: Option<Font>,
+  pub bold_font: Option<Font>,
   pub font_size: u16,
   pub primary: Color, // 60%
   pub secondary: [Color; 5], // 30%
   pub accent: [Color; 2], // 10%
   pub shadow_color: Color,
+  pub info_color: Color,
+  pub success_color: Color,
+  pub warning_color: Color,
+  pub error_color: Color,
+  pub textures: Vec<Texture2D>,
 }
 impl Default for UiTheme {
   fn default() -> Self {
@@ -21,11 +31,26 @@ impl Default for UiTheme {
     let accent_2    = Color::from_hex(0xDC1E70);
     Self {
       font: None,
+      bold_font: None,
       font_size: 18,
       primary,
       secondary: [secondary_1, secondary_2, secondary_3, secondary_4, secondary_5],
       accent: [accent_1, accent_2],
       shadow_color: Color::from_rgba(0, 0, 0, 120),
+      info_color: Color::from_hex(0x3B7DD8),
+      success_color: Color::from_hex(0x3C9A5F),
+      warning_color: Color::from_hex(0xE0A526),
+      error_color: Color::from_hex(0xC9364A),
+      textures: Vec::new(),
     }
   }
 }
+impl UiTheme {
+  pub fn add_texture(&mut self, texture: Texture2D) -> UiTexture {
+    self.textures.push(texture);
+    UiTexture(self.textures.len() - 1)
+  }
+  pub fn texture(&self, handle: UiTexture) -> Option<&Texture2D> {
+    self.textures.get(handle.0)
+  }
+}
@@ -0,0 +1,120 @@
+use crate::mq_ui::*;
+
+const FADE_IN: f32 = 0.2;
+const FADE_OUT: f32 = 0.4;
+pub(crate) const TOAST_W: f32 = 260.0;
+pub(crate) const TOAST_H: f32 = 36.0;
+pub(crate) const TOAST_GAP: f32 = 8.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiToastKind { Info, Success, Warning, Error }
+
+#[derive(Debug, Clone)]
+pub struct UiToast {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub text: String,
+  pub kind: UiToastKind,
+  age: f32,
+  duration: f32,
+  slide_y: Option<f32>,
+}
+impl UiToast {
+  pub(crate) fn new(id: u32, text: &str, kind: UiToastKind, duration: f32) -> Self {
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      abs_bounds: Rect::new(0.0, 0.0, TOAST_W, TOAST_H),
+      text: text.to_owned(),
+      kind,
+      age: 0.0,
+      duration: duration.max(FADE_IN + FADE_OUT),
+      slide_y: None,
+    }
+  }
+  pub fn dismiss(&mut self) {
+    // skip ahead to the fade out
+    self.duration = self.duration.min(self.age.max(FADE_IN) + FADE_OUT);
+  }
+  pub(crate) fn expired(&self) -> bool {
+    self.age >= self.duration
+  }
+  fn opacity(&self) -> f32 {
+    (self.age / FADE_IN).min((self.duration - self.age) / FADE_OUT).clamp(0.0, 1.0)
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    slot: &Rect,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // slide towards stack position
+    let y = match self.slide_y {
+      Some(y) => y + (slot.y - y) * (time_delta * 12.0).min(1.0),
+      None => slot.y
+    };
+    self.slide_y = Some(y);
+    self.abs_bounds = Rect::new(slot.x, y, slot.w, slot.h);
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    if self.event == UiAction::LRelease {
+      self.dismiss();
+    }
+    // pause while hovered, unless already fading out
+    let fading_out = self.duration - self.age <= FADE_OUT;
+    if action_available || fading_out || self.age < FADE_IN {
+      self.age += time_delta;
+    }
+    // clone self into target
+    if !action_available && target.is_none() {
+      target.replace(UiElement::Toast(self.clone()));
+    }
+  }
+  pub(crate) fn render(&self, theme: &UiTheme) {
+    let alpha = self.opacity();
+    let color = match self.kind {
+      UiToastKind::Info => theme.info_color,
+      UiToastKind::Success => theme.success_color,
+      UiToastKind::Warning => theme.warning_color,
+      UiToastKind::Error => theme.error_color,
+    };
+    let b = self.abs_bounds;
+    let shadow = adjust_alpha(&theme.shadow_color, theme.shadow_color.a * alpha);
+    draw_rectangle(b.x - 1.0, b.y - 1.0, b.w + 4.0, b.h + 6.0, shadow);
+    let bg = match self.event {
+      UiAction::Hover | UiAction::Hold => mix_colors(&theme.primary, &color, 0.25),
+      _ => theme.primary
+    };
+    draw_rectangle(b.x, b.y, b.w, b.h, adjust_alpha(&bg, alpha));
+    draw_rectangle(b.x, b.y, 6.0, b.h, adjust_alpha(&color, alpha));
+    // draw text, wrapped inside the toast and cut short if it still overflows
+    let txt_color = contrast_color(&bg);
+    let layout = UiTextLayout::new(&self.text, &UiTextLayoutParams {
+      font: theme.font.as_ref(),
+      bold_font: theme.bold_font.as_ref(),
+      font_size: theme.font_size,
+      bounds: Rect::new(b.x + 14.0, b.y + 2.0, (b.w - 22.0).max(0.0), (b.h - 4.0).max(0.0)),
+      vertical_align: UiTextVAlign::Middle,
+      wrap: true,
+      ellipsis: true,
+      ..Default::default()
+    });
+    layout.draw(theme, adjust_alpha(&txt_color, alpha));
+  }
+}
@@ -0,0 +1,161 @@
+use crate::mq_ui::*;
+
+/// helper struct for building toggle switches
+#[derive(Debug, Clone)]
+pub struct UiToggleParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub label: String,
+  pub checked: bool,
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiToggleParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 100.0, 24.0),
+      alignment: UiAlign::TopLeft,
+      label: String::new(),
+      checked: false,
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiToggle {
+  pub id: u32,
+  pub event: UiAction,
+  holding: bool,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  label: String,
+  pub checked: bool,
+  pub changed: bool,
+  pub focused: bool,
+  knob_pos: f32,
+  pub on_color: Color,
+  pub off_color: Color,
+  pub knob_color: Color,
+  pub focus_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiToggle {
+  pub fn new(id: u32, params: UiToggleParams) -> Self {
+    let mut on_color = BLUE;
+    let mut off_color = GRAY;
+    let mut knob_color = WHITE;
+    let mut focus_color = DARKBLUE;
+    if let Some(tm) = params.theme {
+      on_color = tm.accent[0];
+      off_color = tm.secondary[1];
+      knob_color = tm.primary;
+      focus_color = tm.secondary[3];
+    };
+    Self {
+      id,
+      event: UiAction::None,
+      holding: false,
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      label: params.label,
+      checked: params.checked,
+      changed: false,
+      focused: false,
+      knob_pos: if params.checked { 1.0 } else { 0.0 },
+      on_color,
+      off_color,
+      knob_color,
+      focus_color,
+      data: None
+    }
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    self.abs_bounds = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, time_delta, .. } = frame;
+    // update self
+    let inbounds = point_in_rect(mouse_pos, &self.abs_bounds);
+    let mut action_available = target.is_none();
+    self.event = update_event(
+      &mut action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    self.changed = false;
+    match self.event {
+      UiAction::LRelease => {
+        self.checked = !self.checked;
+        self.changed = true;
+        self.focused = true;
+      }
+      UiAction::LClickOuter => {
+        self.focused = false;
+      }
+      _ => ()
+    };
+    // keyboard activation
+    if self.focused && (is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Enter)) {
+      self.checked = !self.checked;
+      self.changed = true;
+    }
+    // animate knob towards its resting position
+    let knob_target = if self.checked { 1.0 } else { 0.0 };
+    let step = time_delta * 8.0;
+    self.knob_pos += (knob_target - self.knob_pos).clamp(-step, step);
+    // clone self into target, changes are surfaced even when not hovered
+    if self.changed || (!action_available && target.is_none()) {
+      target.replace(UiElement::Toggle(self.clone()));
+    }
+  }
+  pub(crate) fn render(&self, theme: &UiTheme, parent_color: &Color) {
+    // draw track
+    let track = Rect::new(
+      self.abs_bounds.x,
+      self.abs_bounds.y,
+      self.abs_bounds.h * 1.8,
+      self.abs_bounds.h,
+    );
+    let track_color = mix_colors(&self.off_color, &self.on_color, self.knob_pos);
+    draw_pill(&track, track_color);
+    if self.focused {
+      draw_pill_lines(&track, 2.0, self.focus_color);
+    } else {
+      draw_pill_lines(&track, 1.0, BLACK);
+    }
+    // draw knob
+    let knob_r = track.h / 2.0 - 3.0;
+    let knob_x = track.x + track.h / 2.0 + self.knob_pos * (track.w - track.h);
+    let knob_y = track.y + track.h / 2.0;
+    draw_poly(knob_x, knob_y, 24, knob_r, 0.0, self.knob_color);
+    draw_poly_lines(knob_x, knob_y, 24, knob_r, 0.0, 1.0, BLACK);
+    // draw text
+    if !self.label.is_empty() {
+      let txt_size = measure_text(&self.label, theme.font.as_ref(), theme.font_size, 1.0);
+      let txt_x = track.x + track.w + 8.0;
+      let txt_y = self.abs_bounds.y - 2.0 + (txt_size.height + self.abs_bounds.h) / 2.0;
+      draw_ui_text(&self.label, txt_x, txt_y, TextParams {
+        font: theme.font.as_ref(),
+        font_size: theme.font_size,
+        color: contrast_color(parent_color),
+        ..Default::default()
+      });
+    }
+  }
+}
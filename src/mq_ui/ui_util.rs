@@ -1,12 +1,21 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
 use crate::mq_ui::*;
 
+/// ids from here on are handed out to parts the ui creates itself, so user
+/// chosen ids should stay below it
+pub const UI_INTERNAL_ID_START: u32 = 0x8000_0000;
+static NEXT_INTERNAL_ID: AtomicU32 = AtomicU32::new(UI_INTERNAL_ID_START);
+
+/// a unique id for an inner part of an element
+pub(crate) fn internal_id() -> u32 {
+  NEXT_INTERNAL_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 pub fn point_in_rect(point: &(f32, f32), rect: &Rect) -> bool {
-  let x_in = 
-    if point.0 > rect.x && point.0 < rect.x + rect.w { true }
-    else { false };
-  let y_in =
-    if point.1 > rect.y && point.1 < rect.y + rect.h { true }
-    else { false };
+  let x_in = point.0 > rect.x && point.0 < rect.x + rect.w;
+  let y_in = point.1 > rect.y && point.1 < rect.y + rect.h;
   x_in && y_in
 }
 
@@ -16,14 +25,14 @@ pub fn contrast_color(bg_color: &Color) -> Color {
 }
 
 pub fn adjust_alpha(color: &Color, alpha: f32) -> Color {
-  let mut c = color.clone();
+  let mut c = *color;
   c.a = alpha;
   c
 }
 
 pub fn mix_colors(color_1: &Color, color_2: &Color, percent: f32) -> Color {
-  if percent <= 0.0 { return color_1.clone(); }
-  if percent >= 1.0 { return color_2.clone(); }
+  if percent <= 0.0 { return *color_1; }
+  if percent >= 1.0 { return *color_2; }
   let mut c = BLACK;
   c.r = (1.0 - percent) * color_1.r + percent * color_2.r;
   c.g = (1.0 - percent) * color_1.g + percent * color_2.g;
@@ -32,6 +41,152 @@ pub fn mix_colors(color_1: &Color, color_2: &Color, percent: f32) -> Color {
   c
 }
 
+pub fn hsv_to_color(h: f32, s: f32, v: f32, a: f32) -> Color {
+  let h = (h.rem_euclid(1.0)) * 6.0;
+  let c = v * s;
+  let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+  let m = v - c;
+  let (r, g, b) = match h as u32 {
+    0 => (c, x, 0.0),
+    1 => (x, c, 0.0),
+    2 => (0.0, c, x),
+    3 => (0.0, x, c),
+    4 => (x, 0.0, c),
+    _ => (c, 0.0, x),
+  };
+  Color::new(r + m, g + m, b + m, a)
+}
+
+pub fn color_to_hsv(color: &Color) -> (f32, f32, f32) {
+  let max = color.r.max(color.g).max(color.b);
+  let min = color.r.min(color.g).min(color.b);
+  let d = max - min;
+  let mut h = 0.0;
+  if d > 0.0 {
+    if max == color.r { h = ((color.g - color.b) / d).rem_euclid(6.0); }
+    else if max == color.g { h = (color.b - color.r) / d + 2.0; }
+    else { h = (color.r - color.g) / d + 4.0; }
+    h /= 6.0;
+  }
+  let s = if max > 0.0 { d / max } else { 0.0 };
+  (h, s, max)
+}
+
+pub fn color_to_hex(color: &Color) -> String {
+  let c: [u8; 4] = (*color).into();
+  if c[3] == 255 { format!("#{:02X}{:02X}{:02X}", c[0], c[1], c[2]) }
+  else { format!("#{:02X}{:02X}{:02X}{:02X}", c[0], c[1], c[2], c[3]) }
+}
+
+pub fn hex_to_color(hex: &str) -> Option<Color> {
+  let hex = hex.trim_start_matches('#');
+  if hex.len() != 6 && hex.len() != 8 { return None; }
+  let value = u32::from_str_radix(hex, 16).ok()?;
+  if hex.len() == 6 {
+    Some(Color::from_hex(value))
+  } else {
+    let [r, g, b, a] = value.to_be_bytes();
+    Some(Color::from_rgba(r, g, b, a))
+  }
+}
+
+/// draws a rect with colors interpolated between its corners
+pub(crate) fn draw_gradient_rect(rect: &Rect, top_left: Color, top_right: Color, bottom_right: Color, bottom_left: Color) {
+  let mesh = Mesh {
+    vertices: vec![
+      Vertex::new(rect.x, rect.y, 0.0, 0.0, 0.0, top_left),
+      Vertex::new(rect.x + rect.w, rect.y, 0.0, 1.0, 0.0, top_right),
+      Vertex::new(rect.x + rect.w, rect.y + rect.h, 0.0, 1.0, 1.0, bottom_right),
+      Vertex::new(rect.x, rect.y + rect.h, 0.0, 0.0, 1.0, bottom_left),
+    ],
+    indices: vec![0, 1, 2, 0, 2, 3],
+    texture: None,
+  };
+  draw_mesh(&mesh);
+}
+
+pub(crate) fn draw_pill(rect: &Rect, color: Color) {
+  let r = rect.h / 2.0;
+  draw_poly(rect.x + r, rect.y + r, 36, r, 0.0, color);
+  draw_poly(rect.x + rect.w - r, rect.y + r, 36, r, 0.0, color);
+  draw_rectangle(rect.x + r, rect.y, rect.w - rect.h, rect.h, color);
+}
+
+pub(crate) fn draw_pill_lines(rect: &Rect, thickness: f32, color: Color) {
+  let r = rect.h / 2.0;
+  draw_arc(rect.x + r, rect.y + r, 36, r, 90.0, thickness, 180.0, color);
+  draw_arc(rect.x + rect.w - r, rect.y + r, 36, r, 270.0, thickness, 180.0, color);
+  draw_line(
+    rect.x + r,
+    rect.y - thickness / 2.0,
+    rect.x + rect.w - r,
+    rect.y - thickness / 2.0,
+    thickness,
+    color,
+  );
+  draw_line(
+    rect.x + r,
+    rect.y + rect.h + thickness / 2.0,
+    rect.x + rect.w - r,
+    rect.y + rect.h + thickness / 2.0,
+    thickness,
+    color,
+  );
+}
+
+/// calculates destination and source rects for drawing a texture region into bounds
+pub(crate) fn fit_texture(bounds: &Rect, source: &Rect, fit: &UiImageFit) -> (Rect, Rect) {
+  if source.w <= 0.0 || source.h <= 0.0 { return (*bounds, *source); }
+  let mut dest = *bounds;
+  let mut src = *source;
+  match fit {
+    UiImageFit::Stretch => (),
+    UiImageFit::Contain => {
+      let scale = (bounds.w / source.w).min(bounds.h / source.h);
+      dest.w = source.w * scale;
+      dest.h = source.h * scale;
+    }
+    UiImageFit::Cover => {
+      let scale = (bounds.w / source.w).max(bounds.h / source.h);
+      src.w = bounds.w / scale;
+      src.h = bounds.h / scale;
+    }
+    UiImageFit::None => {
+      src.w = source.w.min(bounds.w);
+      src.h = source.h.min(bounds.h);
+      dest.w = src.w;
+      dest.h = src.h;
+    }
+  }
+  // center both the visible region and the destination
+  src.x += (source.w - src.w) / 2.0;
+  src.y += (source.h - src.h) / 2.0;
+  dest.x += (bounds.w - dest.w) / 2.0;
+  dest.y += (bounds.h - dest.h) / 2.0;
+  (dest, src)
+}
+
+pub(crate) fn draw_texture_fit(
+  theme: &UiTheme,
+  handle: UiTexture,
+  bounds: &Rect,
+  source: Option<Rect>,
+  fit: &UiImageFit,
+  tint: Color,
+) {
+  let texture = match theme.texture(handle) {
+    Some(t) => t,
+    None => return
+  };
+  let source = source.unwrap_or(Rect::new(0.0, 0.0, texture.width(), texture.height()));
+  let (dest, src) = fit_texture(bounds, &source, fit);
+  draw_texture_ex(texture, dest.x, dest.y, tint, DrawTextureParams {
+    dest_size: Some(vec2(dest.w, dest.h)),
+    source: Some(src),
+    ..Default::default()
+  });
+}
+
 pub(crate) fn rect_subtract(a: &Rect, b: &Rect) -> Rect {
   Rect {
     x: a.x - b.x,
@@ -41,6 +196,95 @@ pub(crate) fn rect_subtract(a: &Rect, b: &Rect) -> Rect {
   }
 }
 
+thread_local! {
+  // clips currently in effect while rendering, innermost last
+  static CLIP_STACK: RefCell<Vec<Rect>> = const { RefCell::new(Vec::new()) };
+  // physical pixels per ui pixel for the root being updated or rendered
+  static UI_SCALE: Cell<f32> = const { Cell::new(1.0) };
+  // text inputs typed into during the last and the current update
+  static ACTIVE_INPUT: Cell<(Option<u32>, Option<u32>)> = const { Cell::new((None, None)) };
+}
+
+/// starts tracking text input focus for a new update
+pub(crate) fn begin_input_frame() {
+  ACTIVE_INPUT.with(|a| a.set((a.get().1, None)));
+}
+
+pub(crate) fn note_active_input(id: u32) {
+  ACTIVE_INPUT.with(|a| a.set((a.get().0, Some(id))));
+}
+
+/// true if a text input other than the given one takes key presses
+pub(crate) fn other_input_active(id: u32) -> bool {
+  let (last, current) = ACTIVE_INPUT.with(|a| a.get());
+  last.is_some_and(|a| a != id) || current.is_some_and(|a| a != id)
+}
+
+pub(crate) fn set_ui_scale(scale: f32) {
+  UI_SCALE.with(|s| s.set(scale));
+}
+
+/// physical pixels per ui pixel
+pub(crate) fn ui_scale() -> f32 {
+  UI_SCALE.with(|s| s.get())
+}
+
+/// the screen measured in ui pixels
+pub(crate) fn ui_screen_rect() -> Rect {
+  let unit = ui_scale() / screen_dpi_scale();
+  Rect::new(0.0, 0.0, screen_width() / unit, screen_height() / unit)
+}
+
+/// draws text in ui pixels, rasterized at the scaled size so it stays sharp
+pub(crate) fn draw_ui_text(text: &str, x: f32, y: f32, params: TextParams) {
+  let unit = ui_scale() / screen_dpi_scale();
+  if unit == 1.0 || params.font_size == 0 {
+    draw_text_ex(text, x, y, params);
+    return;
+  }
+  let font_size = ((params.font_size as f32 * unit).round() as u16).max(1);
+  let font_scale = params.font_scale * params.font_size as f32 / font_size as f32;
+  draw_text_ex(text, x, y, TextParams { font_size, font_scale, ..params });
+}
+
+/// restricts drawing to the given rect in ui pixels, or lifts the restriction with None
+fn set_clip_rect(rect: Option<&Rect>) {
+  let gl = unsafe { get_internal_gl() };
+  let s = ui_scale();
+  let clip = rect.map(|r| {
+    let (x, y) = ((r.x * s).floor(), (r.y * s).floor());
+    let (w, h) = ((r.x + r.w.max(0.0)) * s - x, (r.y + r.h.max(0.0)) * s - y);
+    (x as i32, y as i32, w.ceil() as i32, h.ceil() as i32)
+  });
+  gl.quad_gl.scissor(clip);
+}
+
+/// restricts drawing to the given rect within any clip already in effect
+///
+/// every push must be paired with a pop once the clipped content is drawn.
+pub(crate) fn push_clip_rect(rect: &Rect) {
+  let clip = CLIP_STACK.with(|stack| {
+    let mut stack = stack.borrow_mut();
+    let clip = match stack.last() {
+      Some(outer) => outer.intersect(*rect).unwrap_or(Rect::new(rect.x, rect.y, 0.0, 0.0)),
+      None => *rect
+    };
+    stack.push(clip);
+    clip
+  });
+  set_clip_rect(Some(&clip));
+}
+
+/// lifts the innermost clip, restoring the one around it
+pub(crate) fn pop_clip_rect() {
+  let outer = CLIP_STACK.with(|stack| {
+    let mut stack = stack.borrow_mut();
+    stack.pop();
+    stack.last().copied()
+  });
+  set_clip_rect(outer.as_ref());
+}
+
 pub(crate) fn get_mouse_actions() -> (UiMouseAction, UiMouseAction) {
   let mut l_mouse = UiMouseAction::None;
   let mut r_mouse = UiMouseAction::None;
@@ -53,147 +297,269 @@ pub(crate) fn get_mouse_actions() -> (UiMouseAction, UiMouseAction) {
   (l_mouse, r_mouse)
 }
 
-pub(crate) fn update_position_adv(
-  prev_abs_bounds: &Rect,
-  prev_rel_bounds: &UiRect,
-  parent_rect: &Rect,
-  parent_delta: &(f32, f32),
-  alignment: &UiAlign,
-  mouse_delta: &(f32, f32),
-  draggable: bool,
-  holding: bool,
-) -> (Rect, UiRect) {
-  let mut abs_bounds = *prev_abs_bounds;
-  let mut rel_bounds = *prev_rel_bounds;
-  // maintain relative size
-  if rel_bounds.w.is_px() {
-    abs_bounds.w = rel_bounds.w.value();
-  } else if rel_bounds.w.is_percent() {
-    abs_bounds.w = rel_bounds.w.value() * parent_rect.w;
-  }
-  if rel_bounds.h.is_px() {
-    abs_bounds.h = rel_bounds.h.value();
-  } else if rel_bounds.h.is_percent() {
-    abs_bounds.h = rel_bounds.h.value() * parent_rect.h;
-  }
-  // drag and parent relative position
-  if draggable && holding {
-    // update absolute positioning
-    abs_bounds.x += mouse_delta.0;
-    abs_bounds.y += mouse_delta.1;
-    // update relative positioning
-    if rel_bounds.x.is_px() {
-      rel_bounds.x += mouse_delta.0;
-    } else if rel_bounds.x.is_percent() {
-      rel_bounds.x += mouse_delta.0 / parent_rect.w;
-    }
-    if rel_bounds.y.is_px() {
-      rel_bounds.y += mouse_delta.1;
-    } else if rel_bounds.y.is_percent() {
-      rel_bounds.y += mouse_delta.1 / parent_rect.h;
-    }
-  } else {
-    // maintain relative distance to parent
-    if rel_bounds.x.is_px() {
-      abs_bounds.x = parent_rect.x + rel_bounds.x.value();
-      // translate parent resize
-      match alignment {
-        UiAlign::TopCenter | UiAlign::FullCenter | UiAlign::BottomCenter => {
-          abs_bounds.x += parent_delta.0 / 2.0;
-          rel_bounds.x += parent_delta.0 / 2.0;
-        }
-        UiAlign::TopRight | UiAlign::CenterRight | UiAlign::BottomRight => {
-          abs_bounds.x += parent_delta.0;
-          rel_bounds.x += parent_delta.0;
-        }
-        _ => ()
-      }
-    } else if rel_bounds.x.is_percent() {
-      abs_bounds.x = parent_rect.x + (rel_bounds.x.value() * parent_rect.w);
-      // translate parent resize
-      match alignment {
-        UiAlign::TopCenter | UiAlign::FullCenter | UiAlign::BottomCenter => {
-          abs_bounds.x += parent_delta.0 / 2.0;
-        }
-        UiAlign::TopRight | UiAlign::CenterRight | UiAlign::BottomRight => {
-          abs_bounds.x += parent_delta.0;
-        }
-        _ => ()
-      }
-    }
-    if rel_bounds.y.is_px() {
-      abs_bounds.y = parent_rect.y + rel_bounds.y.value();
-      // translate parent resize
-      match alignment {
-        UiAlign::CenterLeft | UiAlign::FullCenter | UiAlign::CenterRight => {
-          abs_bounds.y += parent_delta.1 / 2.0;
-          rel_bounds.y += parent_delta.1 / 2.0;
-        }
-        UiAlign::BottomLeft | UiAlign::BottomCenter | UiAlign::BottomRight => {
-          abs_bounds.y += parent_delta.1;
-          rel_bounds.y += parent_delta.1;
-        }
-        _ => ()
-      }
-    } else if rel_bounds.y.is_percent() {
-      abs_bounds.y = parent_rect.y + (rel_bounds.y.value() * parent_rect.h);
-      // translate parent resize
-      match alignment {
-        UiAlign::CenterLeft | UiAlign::FullCenter | UiAlign::CenterRight => {
-          abs_bounds.y += parent_delta.1 / 2.0;
-        }
-        UiAlign::BottomLeft | UiAlign::BottomCenter | UiAlign::BottomRight => {
-          abs_bounds.y += parent_delta.1;
-        }
-        _ => ()
-      }
+/// places a rect inside its parent from an anchor and an offset
+///
+/// offsets point inwards from the anchored edges, so a right aligned x of
+/// 10px keeps the rect 10px away from the parent's right edge, and centered
+/// offsets move away from the center. margins shrink the parent first and
+/// percentages are taken of what is left. the result depends on nothing but
+/// the arguments.
+pub fn anchor_rect(rel_bounds: &UiRect, content_size: &(f32, f32), parent_rect: &Rect, alignment: &UiAlign) -> Rect {
+  let inner = rel_bounds.inner_parent(parent_rect);
+  let (w, h) = rel_bounds.resolve_size(parent_rect, content_size);
+  let (ox, oy) = rel_bounds.resolve_offset(parent_rect);
+  let (ax, ay) = anchor_factors(alignment);
+  let x = inner.x + (inner.w - w) * ax + offset_sign(ax) * ox;
+  let y = inner.y + (inner.h - h) * ay + offset_sign(ay) * oy;
+  Rect::new(x, y, w, h)
+}
+
+/// inverse of anchor_rect, gives relative bounds that place a rect at abs_bounds
+///
+/// each value keeps the unit it has in rel_bounds, auto and fill sizes are
+/// left as they are.
+pub fn anchor_offset(abs_bounds: &Rect, parent_rect: &Rect, alignment: &UiAlign, rel_bounds: &UiRect) -> UiRect {
+  let inner = rel_bounds.inner_parent(parent_rect);
+  let (ax, ay) = anchor_factors(alignment);
+  let x = offset_sign(ax) * (abs_bounds.x - inner.x - (inner.w - abs_bounds.w) * ax);
+  let y = offset_sign(ay) * (abs_bounds.y - inner.y - (inner.h - abs_bounds.h) * ay);
+  UiRect {
+    x: with_unit(&rel_bounds.x, x, inner.w),
+    y: with_unit(&rel_bounds.y, y, inner.h),
+    w: with_unit(&rel_bounds.w, abs_bounds.w, inner.w),
+    h: with_unit(&rel_bounds.h, abs_bounds.h, inner.h),
+    ..*rel_bounds
+  }
+}
+
+fn anchor_factors(alignment: &UiAlign) -> (f32, f32) {
+  match alignment {
+    UiAlign::TopLeft => (0.0, 0.0),
+    UiAlign::TopCenter => (0.5, 0.0),
+    UiAlign::TopRight => (1.0, 0.0),
+    UiAlign::CenterLeft => (0.0, 0.5),
+    UiAlign::FullCenter => (0.5, 0.5),
+    UiAlign::CenterRight => (1.0, 0.5),
+    UiAlign::BottomLeft => (0.0, 1.0),
+    UiAlign::BottomCenter => (0.5, 1.0),
+    UiAlign::BottomRight => (1.0, 1.0),
+  }
+}
+
+fn offset_sign(factor: f32) -> f32 {
+  if factor == 1.0 { -1.0 } else { 1.0 }
+}
+
+fn with_unit(size: &UiSize, px: f32, parent_len: f32) -> UiSize {
+  match size {
+    UiSize::Px(_) => UiSize::Px(px),
+    UiSize::Percent(p) => {
+      if parent_len != 0.0 { UiSize::Percent(px / parent_len) }
+      else { UiSize::Percent(*p) }
     }
+    UiSize::Calc(p, _) => UiSize::Calc(*p, px - p * parent_len),
+    UiSize::Auto | UiSize::Fill => *size
   }
-  // return new positions
-  (abs_bounds, rel_bounds)
 }
 
+/// positions children and their subtrees inside parent_rect
+pub(crate) fn arrange_children(children: &mut [UiElement], parent_rect: &Rect) {
+  for elem in children.iter_mut() {
+    arrange_element(elem, parent_rect);
+  }
+}
+
+/// positions children inside slots given by their container's layout
+pub(crate) fn arrange_slotted_children(children: &mut [UiElement], slots: &[Rect], parent_rect: &Rect) {
+  for (elem, slot) in children.iter_mut().zip(slots.iter()) {
+    let rel = match elem.rel_bounds() {
+      Some(rel) => rel,
+      None => continue
+    };
+    // fill the slot inside the child's margin, collapsibles keep their
+    // expanded height and animate within it
+    let h = match elem {
+      UiElement::Collapsible(_) => UiSize::Px(rel.resolve_size(parent_rect, &(0.0, 0.0)).1),
+      _ => UiSize::Percent(1.0)
+    };
+    elem.set_rel_bounds(UiRect {
+      x: UiSize::Px(0.0),
+      y: UiSize::Px(0.0),
+      w: UiSize::Percent(1.0),
+      h,
+      ..rel
+    });
+    arrange_element(elem, slot);
+    elem.set_rel_bounds(rel);
+  }
+}
+
+fn arrange_element(elem: &mut UiElement, parent_rect: &Rect) {
+  match elem {
+    UiElement::Box(e) => e.arrange(parent_rect),
+    UiElement::Text(e) => e.arrange(parent_rect),
+    UiElement::Button(e) => e.arrange(parent_rect),
+    UiElement::Input(e) => e.arrange(parent_rect),
+    UiElement::Radio(e) => e.arrange(parent_rect),
+    UiElement::Window(e) => e.arrange(parent_rect),
+    UiElement::Toggle(e) => e.arrange(parent_rect),
+    UiElement::Image(e) => e.arrange(parent_rect),
+    UiElement::NumberInput(e) => e.arrange(parent_rect),
+    UiElement::ColorPicker(e) => e.arrange(parent_rect),
+    // toasts are owned and laid out by the root
+    UiElement::Toast(_) => (),
+    UiElement::Split(e) => e.arrange(parent_rect),
+    UiElement::Canvas(e) => e.arrange(parent_rect),
+    UiElement::Plot(e) => e.arrange(parent_rect),
+    UiElement::Console(e) => e.arrange(parent_rect),
+    UiElement::Collapsible(e) => e.arrange(parent_rect),
+    UiElement::Progress(e) => e.arrange(parent_rect),
+    UiElement::Spinner(e) => e.arrange(parent_rect),
+  }
+}
+
+/// handles input for children, hit-testing against their arranged bounds
+///
+/// layout_dirty is set when a child moved or resized something.
 pub(crate) fn update_children(
   children: &mut Vec<UiElement>, 
+  z_index: &HashMap<u32, i32>,
   target: &mut Option<UiElement>,
   parent_rect: &Rect,
-  parent_delta: &(f32, f32),
-  mouse_pos: &(f32, f32),
-  mouse_delta: &(f32, f32),
-  l_mouse: &UiMouseAction,
-  r_mouse: &UiMouseAction,
-  time_delta: &f32,
+  frame: &UiFrameInput,
+  layout_dirty: &mut bool,
 ) {
   // update children in reverse order
   for elem in children.iter_mut().rev() {
-    match elem {
-      UiElement::Box(e) => {
-        e.update(target, parent_rect, parent_delta, mouse_pos, mouse_delta, l_mouse, r_mouse, time_delta);
-      }
-      UiElement::Text(e) => {
-        e.update(target, parent_rect, parent_delta, mouse_pos, mouse_delta, l_mouse, r_mouse);
-      }
-      UiElement::Button(e) => {
-        e.update(target, parent_rect, parent_delta, mouse_pos, mouse_delta, l_mouse, r_mouse);
-      }
-      UiElement::Input(e) => {
-        e.update(target, parent_rect, parent_delta, mouse_pos, mouse_delta, l_mouse, r_mouse, time_delta);
-      }
-      UiElement::Radio(e) => {
-        e.update(target, parent_rect, parent_delta, mouse_pos, mouse_delta, l_mouse, r_mouse);
-      }
+    update_element(elem, target, parent_rect, frame, layout_dirty);
+  }
+  // reordering moves children in row, column and grid layouts
+  if settle_children(children, z_index) { *layout_dirty = true; }
+}
+
+fn update_element(
+  elem: &mut UiElement,
+  target: &mut Option<UiElement>,
+  parent_rect: &Rect,
+  frame: &UiFrameInput,
+  layout_dirty: &mut bool,
+) {
+  match elem {
+    UiElement::Box(e) => {
+      e.update(target, parent_rect, frame, layout_dirty);
+    }
+    UiElement::Text(e) => {
+      e.update(target, parent_rect, frame, layout_dirty);
+    }
+    UiElement::Button(e) => {
+      e.update(target, frame, layout_dirty);
+    }
+    UiElement::Input(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Radio(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Window(e) => {
+      e.update(target, parent_rect, frame, layout_dirty);
+    }
+    UiElement::Toggle(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Image(e) => {
+      e.update(target, frame);
+    }
+    UiElement::NumberInput(e) => {
+      e.update(target, frame);
+    }
+    UiElement::ColorPicker(e) => {
+      e.update(target, frame);
+    }
+    // toasts are owned and laid out by the root
+    UiElement::Toast(_) => (),
+    UiElement::Split(e) => {
+      e.update(target, frame, layout_dirty);
+    }
+    UiElement::Canvas(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Plot(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Console(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Collapsible(e) => {
+      e.update(target, frame, layout_dirty);
+    }
+    UiElement::Progress(e) => {
+      e.update(target, frame);
+    }
+    UiElement::Spinner(e) => {
+      e.update(target, frame);
     }
   }
 }
 
+/// applies sibling rules after an update: raising, z-order and accordion groups
+///
+/// returns true if the order of children changed.
+fn settle_children(children: &mut Vec<UiElement>, z_index: &HashMap<u32, i32>) -> bool {
+  // bring clicked windows and boxes to the front
+  let raised = children.iter_mut().position(|elem| match elem {
+    UiElement::Window(e) => std::mem::take(&mut e.raise),
+    UiElement::Box(e) => std::mem::take(&mut e.raise),
+    _ => false
+  });
+  let mut relayout = false;
+  if let Some(idx) = raised {
+    if idx + 1 < children.len() {
+      let elem = children.remove(idx);
+      children.push(elem);
+      relayout = true;
+    }
+  }
+  if sort_by_z_index(children, z_index) { relayout = true; }
+  // one open section per accordion group, the one opened this update or
+  // else the first open one, the rest animate closed
+  let mut open_groups: Vec<u32> = children.iter().filter_map(|elem| match elem {
+    UiElement::Collapsible(e) if e.changed && e.expanded => e.group,
+    _ => None
+  }).collect();
+  for elem in children.iter_mut() {
+    if let UiElement::Collapsible(e) = elem {
+      let group = match e.group {
+        Some(group) if e.expanded && !e.changed => group,
+        _ => continue
+      };
+      if open_groups.contains(&group) { e.expanded = false; }
+      else { open_groups.push(group); }
+    }
+  }
+  relayout
+}
+
 pub(crate) fn render_children(children: &mut Vec<UiElement>, theme: &UiTheme, parent_color: &Color) {
   for elem in children {
     match elem {
-      UiElement::Box(e) => { e.render(&theme); }
-      UiElement::Text(e) => { e.render(&theme, parent_color); }
-      UiElement::Button(e) => { e.render(&theme); }
-      UiElement::Input(e) => { e.render(&theme); }
-      UiElement::Radio(e) => { e.render(&theme, parent_color); }
+      UiElement::Box(e) => { e.render(theme); }
+      UiElement::Text(e) => { e.render(theme, parent_color); }
+      UiElement::Button(e) => { e.render(theme); }
+      UiElement::Input(e) => { e.render(theme); }
+      UiElement::Radio(e) => { e.render(theme, parent_color); }
+      UiElement::Window(e) => { e.render(theme); }
+      UiElement::Toggle(e) => { e.render(theme, parent_color); }
+      UiElement::Image(e) => { e.render(theme); }
+      UiElement::NumberInput(e) => { e.render(theme); }
+      UiElement::ColorPicker(e) => { e.render(theme); }
+      UiElement::Toast(_) => (),
+      UiElement::Split(e) => { e.render(theme); }
+      UiElement::Canvas(e) => { e.render(theme); }
+      UiElement::Plot(e) => { e.render(theme); }
+      UiElement::Console(e) => { e.render(theme); }
+      UiElement::Collapsible(e) => { e.render(theme); }
+      UiElement::Progress(e) => { e.render(theme); }
+      UiElement::Spinner(e) => { e.render(); }
     }
   }
 }
@@ -259,11 +625,124 @@ pub(crate) fn find_node(children: &Vec<UiElement>, id: u32) -> Option<&UiElement
       UiElement::Radio(e) => {
         if e.id == id { out = Some(elem); }
       }
+      UiElement::Window(e) => {
+        if e.id == id { out = Some(elem); }
+        let deep = find_node(&e.children, id);
+        if deep.is_some() { out = deep; }
+      }
+      UiElement::Toggle(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Image(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::NumberInput(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::ColorPicker(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Toast(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Canvas(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Plot(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Console(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Progress(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Spinner(e) => {
+        if e.id == id { out = Some(elem); }
+      }
+      UiElement::Collapsible(e) => {
+        if e.id == id { out = Some(elem); }
+        let deep = find_node(&e.content.children, id);
+        if deep.is_some() { out = deep; }
+      }
+      UiElement::Split(e) => {
+        if e.id == id { out = Some(elem); }
+        let deep = find_node(&e.first.children, id);
+        if deep.is_some() { out = deep; }
+        let deep = find_node(&e.second.children, id);
+        if deep.is_some() { out = deep; }
+      }
     }
   }
   out
 }
 
+/// keeps children ordered by z-index, leaving the order within a z-index alone
+///
+/// returns true if anything moved.
+pub(crate) fn sort_by_z_index(children: &mut [UiElement], z_index: &HashMap<u32, i32>) -> bool {
+  if z_index.is_empty() { return false; }
+  let z = |elem: &UiElement| z_index.get(&elem.id()).copied().unwrap_or(0);
+  if children.windows(2).all(|pair| z(&pair[0]) <= z(&pair[1])) { return false; }
+  children.sort_by_key(z);
+  true
+}
+
+/// finds the children and z-indices of the container holding the element with the given id
+pub(crate) fn find_siblings_mut<'a>(
+  children: &'a mut Vec<UiElement>,
+  z_index: &'a mut HashMap<u32, i32>,
+  id: u32,
+) -> Option<(&'a mut Vec<UiElement>, &'a mut HashMap<u32, i32>)> {
+  if children.iter().any(|elem| elem.id() == id) { return Some((children, z_index)); }
+  for elem in children.iter_mut() {
+    let deep = match elem {
+      UiElement::Box(e) => find_siblings_mut(&mut e.children, &mut e.z_index, id),
+      UiElement::Window(e) => find_siblings_mut(&mut e.children, &mut e.z_index, id),
+      UiElement::Collapsible(e) => find_siblings_mut(&mut e.content.children, &mut e.content.z_index, id),
+      UiElement::Split(e) => {
+        let first = find_siblings_mut(&mut e.first.children, &mut e.first.z_index, id);
+        if first.is_some() { first }
+        else { find_siblings_mut(&mut e.second.children, &mut e.second.z_index, id) }
+      }
+      _ => None
+    };
+    if deep.is_some() { return deep; }
+  }
+  None
+}
+
+/// moves a child to the front or back of the children sharing its z-index
+pub(crate) fn restack_child(children: &mut Vec<UiElement>, z_index: &HashMap<u32, i32>, id: u32, front: bool) {
+  let idx = match children.iter().position(|elem| elem.id() == id) {
+    Some(idx) => idx,
+    None => return
+  };
+  let elem = children.remove(idx);
+  if front { children.push(elem); }
+  else { children.insert(0, elem); }
+  sort_by_z_index(children, z_index);
+}
+
+pub(crate) fn find_node_mut(children: &mut [UiElement], id: u32) -> Option<&mut UiElement> {
+  for elem in children.iter_mut() {
+    if elem.id() == id { return Some(elem); }
+    let deep = match elem {
+      UiElement::Box(e) => find_node_mut(&mut e.children, id),
+      UiElement::Window(e) => find_node_mut(&mut e.children, id),
+      UiElement::Collapsible(e) => find_node_mut(&mut e.content.children, id),
+      UiElement::Split(e) => {
+        let first = find_node_mut(&mut e.first.children, id);
+        if first.is_some() { first }
+        else { find_node_mut(&mut e.second.children, id) }
+      }
+      _ => None
+    };
+    if deep.is_some() { return deep; }
+  }
+  None
+}
+
 pub(crate) fn key_code_to_char(k: &KeyCode) -> (&str, &str) {
   match k {
     KeyCode::Space => (" ", " "),
@@ -332,3 +811,124 @@ pub(crate) fn key_code_to_char(k: &KeyCode) -> (&str, &str) {
     _ => ("", "")
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ALIGNS: [UiAlign; 9] = [
+    UiAlign::TopLeft, UiAlign::TopCenter, UiAlign::TopRight,
+    UiAlign::CenterLeft, UiAlign::FullCenter, UiAlign::CenterRight,
+    UiAlign::BottomLeft, UiAlign::BottomCenter, UiAlign::BottomRight,
+  ];
+
+  // small deterministic generator so failures are reproducible
+  struct Lcg(u64);
+  impl Lcg {
+    fn next(&mut self) -> f32 {
+      self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+    fn range(&mut self, lo: f32, hi: f32) -> f32 {
+      lo + (hi - lo) * self.next()
+    }
+  }
+
+  fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-3 * (1.0 + a.abs().max(b.abs()))
+  }
+
+  fn rect_close(a: &Rect, b: &Rect) -> bool {
+    close(a.x, b.x) && close(a.y, b.y) && close(a.w, b.w) && close(a.h, b.h)
+  }
+
+  fn size_close(a: &UiSize, b: &UiSize) -> bool {
+    match (a, b) {
+      (UiSize::Px(a), UiSize::Px(b)) | (UiSize::Percent(a), UiSize::Percent(b)) => close(*a, *b),
+      _ => false
+    }
+  }
+
+  // every combination of px and percent for x, y, w and h
+  fn sample_rects(rng: &mut Lcg) -> Vec<UiRect> {
+    (0..16).map(|units| {
+      let pick = |bit: u32, px: f32, pct: f32| {
+        if units & (1 << bit) == 0 { UiSize::Px(px) } else { UiSize::Percent(pct) }
+      };
+      UiRect {
+        x: pick(0, rng.range(-50.0, 150.0), rng.range(-0.2, 0.6)),
+        y: pick(1, rng.range(-50.0, 150.0), rng.range(-0.2, 0.6)),
+        w: pick(2, rng.range(1.0, 300.0), rng.range(0.05, 1.0)),
+        h: pick(3, rng.range(1.0, 300.0), rng.range(0.05, 1.0)),
+        ..Default::default()
+      }
+    }).collect()
+  }
+
+  fn sample_parent(rng: &mut Lcg) -> Rect {
+    Rect::new(rng.range(-100.0, 100.0), rng.range(-100.0, 100.0), rng.range(50.0, 1200.0), rng.range(50.0, 900.0))
+  }
+
+  #[test]
+  fn anchor_rect_depends_only_on_its_inputs() {
+    let mut rng = Lcg(1);
+    for _ in 0..20 {
+      let parent = sample_parent(&mut rng);
+      let other = sample_parent(&mut rng);
+      for rel in sample_rects(&mut rng) {
+        for align in ALIGNS.iter() {
+          let first = anchor_rect(&rel, &(0.0, 0.0), &parent, align);
+          let _ = anchor_rect(&rel, &(0.0, 0.0), &other, align);
+          let again = anchor_rect(&rel, &(0.0, 0.0), &parent, align);
+          assert_eq!(first, again, "{:?} {:?}", rel, align);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn anchor_offset_inverts_anchor_rect() {
+    let mut rng = Lcg(2);
+    for _ in 0..20 {
+      let parent = sample_parent(&mut rng);
+      for rel in sample_rects(&mut rng) {
+        for align in ALIGNS.iter() {
+          let abs = anchor_rect(&rel, &(0.0, 0.0), &parent, align);
+          let back = anchor_offset(&abs, &parent, align, &rel);
+          assert!(size_close(&back.x, &rel.x), "x {:?} {:?} {:?}", rel, back, align);
+          assert!(size_close(&back.y, &rel.y), "y {:?} {:?} {:?}", rel, back, align);
+          assert!(size_close(&back.w, &rel.w), "w {:?} {:?} {:?}", rel, back, align);
+          assert!(size_close(&back.h, &rel.h), "h {:?} {:?} {:?}", rel, back, align);
+          // and a moved rect comes back where it was put
+          let moved = Rect::new(abs.x + rng.range(-40.0, 40.0), abs.y + rng.range(-40.0, 40.0), abs.w, abs.h);
+          let rel_moved = anchor_offset(&moved, &parent, align, &rel);
+          let placed = anchor_rect(&rel_moved, &(0.0, 0.0), &parent, align);
+          assert!(rect_close(&placed, &moved), "{:?} {:?} {:?}", moved, placed, align);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn repeated_resizes_do_not_drift() {
+    let mut rng = Lcg(3);
+    let start = Rect::new(0.0, 0.0, 800.0, 600.0);
+    for rel in sample_rects(&mut rng) {
+      for align in ALIGNS.iter() {
+        let expected = anchor_rect(&rel, &(0.0, 0.0), &start, align);
+        // the first frame can report a zero size screen, and the offset is
+        // written back every frame as a drag would
+        let mut current = rel;
+        let mut sizes = vec![(0.0, 0.0)];
+        sizes.extend((0..50).map(|_| (rng.range(0.0, 1600.0), rng.range(0.0, 1200.0))));
+        for (w, h) in sizes {
+          let parent = Rect::new(0.0, 0.0, w, h);
+          let abs = anchor_rect(&current, &(0.0, 0.0), &parent, align);
+          current = anchor_offset(&abs, &parent, align, &current);
+        }
+        let after = anchor_rect(&current, &(0.0, 0.0), &start, align);
+        assert!(rect_close(&after, &expected), "{:?} {:?} {:?} {:?}", rel, align, expected, after);
+      }
+    }
+  }
+}
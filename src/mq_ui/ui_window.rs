@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use miniquad::CursorIcon;
+use crate::mq_ui::*;
+
+const TITLE_H: f32 = 25.0;
+const RESIZE_MARGIN: f32 = 4.0;
+
+/// helper struct for building windows
+#[derive(Debug, Clone)]
+pub struct UiWindowParams<'a> {
+  pub pos_size: UiRect,
+  pub alignment: UiAlign,
+  pub title: String,
+  pub closable: bool,
+  pub minimizable: bool,
+  pub resizable: bool,
+  pub min_size: (f32, f32),
+  pub theme: Option<&'a UiTheme>,
+}
+impl Default for UiWindowParams<'_> {
+  fn default() -> Self {
+    Self {
+      pos_size: UiRect::from_px(0.0, 0.0, 300.0, 200.0),
+      alignment: UiAlign::TopLeft,
+      title: "Window".to_owned(),
+      closable: true,
+      minimizable: true,
+      resizable: true,
+      min_size: (120.0, 60.0),
+      theme: None,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UiWindowEvent { None, Close, Minimize, Restore, Move, Resize }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum UiWindowZone { None, Body, TitleBar, Close, Minimize, Resize(i8, i8) }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum UiWindowDrag { None, Move, Resize(i8, i8) }
+
+#[derive(Debug, Clone)]
+pub struct UiWindow {
+  pub id: u32,
+  pub event: UiAction,
+  pub window_event: UiWindowEvent,
+  holding: bool,
+  pub(crate) children: Vec<UiElement>,
+  pub(crate) z_index: HashMap<u32, i32>,
+  abs_bounds: Rect,
+  pub(crate) rel_bounds: UiRect,
+  alignment: UiAlign,
+  hover_zone: UiWindowZone,
+  press_zone: UiWindowZone,
+  drag: UiWindowDrag,
+  drag_origin: (f32, f32),
+  drag_start: Rect,
+  pub(crate) raise: bool,
+  pub title: String,
+  pub closable: bool,
+  pub minimizable: bool,
+  pub resizable: bool,
+  pub min_size: (f32, f32),
+  pub open: bool,
+  pub minimized: bool,
+  pub color: Color,
+  pub title_color: Color,
+  pub title_hover_color: Color,
+  pub data: Option<UiMetaData>
+}
+impl UiWindow {
+  pub fn new(id: u32, params: UiWindowParams) -> Self {
+    let mut color = GRAY;
+    let mut title_color = DARKGRAY;
+    let mut title_hover_color = LIGHTGRAY;
+    if let Some(tm) = params.theme {
+      color = tm.secondary[0];
+      title_color = tm.secondary[2];
+      title_hover_color = tm.secondary[3];
+    }
+    Self {
+      id,
+      event: UiAction::None,
+      window_event: UiWindowEvent::None,
+      holding: false,
+      children: Vec::new(),
+      z_index: HashMap::new(),
+      rel_bounds: params.pos_size,
+      abs_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+      alignment: params.alignment,
+      hover_zone: UiWindowZone::None,
+      press_zone: UiWindowZone::None,
+      drag: UiWindowDrag::None,
+      drag_origin: (0.0, 0.0),
+      drag_start: Rect::new(0.0, 0.0, 0.0, 0.0),
+      raise: false,
+      title: params.title,
+      closable: params.closable,
+      minimizable: params.minimizable,
+      resizable: params.resizable,
+      min_size: params.min_size,
+      open: true,
+      minimized: false,
+      color,
+      title_color,
+      title_hover_color,
+      data: None,
+    }
+  }
+  pub fn with<F>(mut self, func: F) -> Self
+  where F: Fn(&mut UiWindow) {
+    func(&mut self);
+    self
+  }
+  pub fn with_meta_data(mut self, meta_data: UiMetaData) -> Self {
+    self.data = Some(meta_data);
+    self
+  }
+  /// current absolute bounds, including the title bar
+  pub fn bounds(&self) -> Rect {
+    self.abs_bounds
+  }
+  /// current relative bounds, suitable for restoring the window later
+  pub fn pos_size(&self) -> UiRect {
+    self.rel_bounds
+  }
+  /// positions self on screen and lays out children inside the body
+  pub(crate) fn arrange(&mut self, parent_rect: &Rect) {
+    // clamping only touches the placement, so the window returns to its
+    // offset once the screen is large enough again
+    let placed = anchor_rect(&self.rel_bounds, &(0.0, 0.0), parent_rect, &self.alignment);
+    self.abs_bounds = self.clamped_to_screen(&placed);
+    let body = self.body_rect();
+    arrange_children(&mut self.children, &body);
+  }
+  pub(crate) fn update(
+    &mut self,
+    target: &mut Option<UiElement>,
+    parent_rect: &Rect,
+    frame: &UiFrameInput,
+    layout_dirty: &mut bool,
+  ) {
+    let UiFrameInput { mouse_pos, .. } = frame;
+    self.window_event = UiWindowEvent::None;
+    if !self.open {
+      self.event = UiAction::None;
+      self.hover_zone = UiWindowZone::None;
+      return;
+    }
+    // move or resize from drag
+    if self.holding && self.drag != UiWindowDrag::None {
+      let prev = self.abs_bounds;
+      self.apply_drag(parent_rect, mouse_pos);
+      if self.abs_bounds != prev {
+        self.arrange(parent_rect);
+        *layout_dirty = true;
+      }
+    }
+    // title bar and resize handles take priority over body content
+    let zone = self.zone_at(mouse_pos);
+    let chrome = self.drag != UiWindowDrag::None || !matches!(zone, UiWindowZone::None | UiWindowZone::Body);
+    let mut action_available = target.is_none();
+    if chrome {
+      self.update_event(target, &mut action_available, zone, frame);
+    }
+    // update children
+    if !self.minimized {
+      let body = self.body_rect();
+      update_children(
+        &mut self.children,
+        &self.z_index,
+        target,
+        &body,
+        frame,
+        layout_dirty,
+      );
+    }
+    if !chrome {
+      action_available = target.is_none();
+      self.update_event(target, &mut action_available, zone, frame);
+    }
+    match self.window_event {
+      UiWindowEvent::Minimize | UiWindowEvent::Restore => *layout_dirty = true,
+      _ => ()
+    }
+    // window events are surfaced even if something else is hovered
+    if self.window_event != UiWindowEvent::None {
+      target.replace(UiElement::Window(self.clone()));
+    }
+  }
+  fn update_event(
+    &mut self,
+    target: &mut Option<UiElement>,
+    action_available: &mut bool,
+    zone: UiWindowZone,
+    frame: &UiFrameInput,
+  ) {
+    let UiFrameInput { mouse_pos, l_mouse, r_mouse, .. } = frame;
+    let inbounds = zone != UiWindowZone::None;
+    self.event = update_event(
+      action_available,
+      inbounds,
+      &mut self.holding,
+      &self.event,
+      l_mouse,
+      r_mouse,
+    );
+    self.hover_zone = if *action_available { UiWindowZone::None } else { zone };
+    match self.event {
+      UiAction::LClick => {
+        self.raise = true;
+        self.press_zone = zone;
+        self.drag = match zone {
+          UiWindowZone::TitleBar => UiWindowDrag::Move,
+          UiWindowZone::Resize(x, y) => UiWindowDrag::Resize(x, y),
+          _ => UiWindowDrag::None
+        };
+        self.drag_start = self.abs_bounds;
+        self.drag_origin = *mouse_pos;
+      }
+      UiAction::LRelease if self.press_zone == zone => {
+        match zone {
+          UiWindowZone::Close => {
+            self.open = false;
+            self.window_event = UiWindowEvent::Close;
+          }
+          UiWindowZone::Minimize => {
+            self.minimized = !self.minimized;
+            self.window_event = if self.minimized { UiWindowEvent::Minimize }
+              else { UiWindowEvent::Restore };
+          }
+          _ => ()
+        }
+      }
+      _ => ()
+    }
+    // finish drag
+    if !self.holding && self.drag != UiWindowDrag::None {
+      if self.abs_bounds != self.drag_start {
+        self.window_event = match self.drag {
+          UiWindowDrag::Move => UiWindowEvent::Move,
+          _ => UiWindowEvent::Resize
+        };
+      }
+      self.drag = UiWindowDrag::None;
+    }
+    // clone self into target
+    if !*action_available && target.is_none() {
+      target.replace(UiElement::Window(self.clone()));
+    }
+  }
+  fn apply_drag(&mut self, parent_rect: &Rect, mouse_pos: &(f32, f32)) {
+    let dx = mouse_pos.0 - self.drag_origin.0;
+    let dy = mouse_pos.1 - self.drag_origin.1;
+    let start = self.drag_start;
+    let scrn = ui_screen_rect();
+    let (scrn_w, scrn_h) = (scrn.w, scrn.h);
+    let mut next = start;
+    match self.drag {
+      UiWindowDrag::Move => {
+        next.x += dx;
+        next.y += dy;
+      }
+      UiWindowDrag::Resize(ex, ey) => {
+        if ex < 0 {
+          next.x = (start.x + dx).max(0.0).min(start.x + start.w - self.min_size.0);
+          next.w = start.x + start.w - next.x;
+        } else if ex > 0 {
+          next.w = (start.w + dx).min(scrn_w - start.x).max(self.min_size.0);
+        }
+        let min_h = self.min_size.1 + TITLE_H;
+        if ey < 0 {
+          next.y = (start.y + dy).max(0.0).min(start.y + start.h - min_h);
+          next.h = start.y + start.h - next.y;
+        } else if ey > 0 {
+          next.h = (start.h + dy).min(scrn_h - start.y).max(min_h);
+        }
+      }
+      UiWindowDrag::None => ()
+    }
+    let next = self.clamped_to_screen(&next);
+    self.shift_bounds(&next, parent_rect);
+  }
+  /// keeps the title bar of a window placed at rect on screen
+  fn clamped_to_screen(&self, rect: &Rect) -> Rect {
+    let scrn = ui_screen_rect();
+    let (scrn_w, scrn_h) = (scrn.w, scrn.h);
+    let frame_h = if self.minimized { TITLE_H } else { rect.h };
+    let mut next = *rect;
+    next.w = next.w.min(scrn_w);
+    next.h = next.h.min(scrn_h);
+    next.x = next.x.min(scrn_w - next.w).max(0.0);
+    next.y = next.y.min(scrn_h - frame_h.min(next.h)).max(0.0);
+    next
+  }
+  /// moves the window to the given absolute bounds, keeping relative bounds in sync
+  fn shift_bounds(&mut self, next: &Rect, parent_rect: &Rect) {
+    self.rel_bounds = anchor_offset(next, parent_rect, &self.alignment, &self.rel_bounds);
+    self.abs_bounds = *next;
+  }
+  fn zone_at(&self, point: &(f32, f32)) -> UiWindowZone {
+    let frame = self.frame_rect();
+    let can_resize = self.resizable && !self.minimized;
+    let m = if can_resize { RESIZE_MARGIN } else { 0.0 };
+    let outer = Rect::new(frame.x - m, frame.y - m, frame.w + 2.0 * m, frame.h + 2.0 * m);
+    if !point_in_rect(point, &outer) { return UiWindowZone::None; }
+    if can_resize {
+      let ex = if point.0 < frame.x + m { -1 }
+        else if point.0 > frame.x + frame.w - m { 1 }
+        else { 0 };
+      let ey = if point.1 < frame.y + m { -1 }
+        else if point.1 > frame.y + frame.h - m { 1 }
+        else { 0 };
+      if ex != 0 || ey != 0 { return UiWindowZone::Resize(ex, ey); }
+    }
+    if self.closable && point_in_rect(point, &self.close_rect()) {
+      return UiWindowZone::Close;
+    }
+    if self.minimizable && point_in_rect(point, &self.minimize_rect()) {
+      return UiWindowZone::Minimize;
+    }
+    if point.1 < frame.y + TITLE_H { UiWindowZone::TitleBar }
+    else { UiWindowZone::Body }
+  }
+  pub(crate) fn cursor(&self) -> Option<CursorIcon> {
+    let zone = match self.drag {
+      UiWindowDrag::Resize(x, y) => UiWindowZone::Resize(x, y),
+      UiWindowDrag::Move => UiWindowZone::TitleBar,
+      UiWindowDrag::None => self.hover_zone
+    };
+    match zone {
+      UiWindowZone::Resize(0, _) => Some(CursorIcon::NSResize),
+      UiWindowZone::Resize(_, 0) => Some(CursorIcon::EWResize),
+      UiWindowZone::Resize(x, y) => {
+        if x == y { Some(CursorIcon::NWSEResize) }
+        else { Some(CursorIcon::NESWResize) }
+      }
+      UiWindowZone::Close | UiWindowZone::Minimize => Some(CursorIcon::Pointer),
+      UiWindowZone::TitleBar => Some(CursorIcon::Move),
+      _ => None
+    }
+  }
+  fn frame_rect(&self) -> Rect {
+    let mut frame = self.abs_bounds;
+    if self.minimized { frame.h = TITLE_H; }
+    frame
+  }
+  fn body_rect(&self) -> Rect {
+    Rect::new(
+      self.abs_bounds.x,
+      self.abs_bounds.y + TITLE_H,
+      self.abs_bounds.w,
+      (self.abs_bounds.h - TITLE_H).max(0.0),
+    )
+  }
+  fn close_rect(&self) -> Rect {
+    Rect::new(
+      self.abs_bounds.x + self.abs_bounds.w - TITLE_H + 3.0,
+      self.abs_bounds.y + 3.0,
+      TITLE_H - 6.0,
+      TITLE_H - 6.0,
+    )
+  }
+  fn minimize_rect(&self) -> Rect {
+    let mut rect = self.close_rect();
+    if self.closable { rect.x -= TITLE_H - 2.0; }
+    rect
+  }
+  pub(crate) fn render(&mut self, theme: &UiTheme) {
+    if !self.open { return; }
+    let frame = self.frame_rect();
+    draw_rectangle(frame.x - 1.0, frame.y - 1.0, frame.w + 4.0, frame.h + 6.0, theme.shadow_color);
+    // draw title bar
+    let title_active = self.drag == UiWindowDrag::Move || self.hover_zone == UiWindowZone::TitleBar;
+    let title_color = if title_active { self.title_hover_color } else { self.title_color };
+    draw_rectangle(frame.x, frame.y, frame.w, TITLE_H, title_color);
+    let txt_size = measure_text(&self.title, theme.font.as_ref(), theme.font_size, 1.0);
+    let txt_y = frame.y + txt_size.offset_y + (TITLE_H - txt_size.height) / 2.0;
+    draw_ui_text(&self.title, frame.x + 8.0, txt_y, TextParams {
+      font: theme.font.as_ref(),
+      font_size: theme.font_size,
+      color: contrast_color(&title_color),
+      ..Default::default()
+    });
+    // draw title bar buttons
+    let icon_color = contrast_color(&title_color);
+    if self.closable {
+      let btn = self.close_rect();
+      if self.hover_zone == UiWindowZone::Close {
+        draw_rectangle(btn.x, btn.y, btn.w, btn.h, theme.accent[1]);
+      }
+      draw_line(btn.x + 5.0, btn.y + 5.0, btn.x + btn.w - 5.0, btn.y + btn.h - 5.0, 1.5, icon_color);
+      draw_line(btn.x + btn.w - 5.0, btn.y + 5.0, btn.x + 5.0, btn.y + btn.h - 5.0, 1.5, icon_color);
+    }
+    if self.minimizable {
+      let btn = self.minimize_rect();
+      if self.hover_zone == UiWindowZone::Minimize {
+        draw_rectangle(btn.x, btn.y, btn.w, btn.h, self.title_hover_color);
+      }
+      draw_line(btn.x + 5.0, btn.y + btn.h - 5.0, btn.x + btn.w - 5.0, btn.y + btn.h - 5.0, 1.5, icon_color);
+    }
+    if self.minimized { return; }
+    // draw body
+    let body = self.body_rect();
+    draw_rectangle(body.x, body.y, body.w, body.h, self.color);
+    // render children
+    render_children(&mut self.children, theme, &self.color);
+  }
+  pub fn add_child(&mut self, elem: UiElement) {
+    self.children.push(elem);
+  }
+  /// children with a higher z-index are drawn above and hit before the rest
+  pub fn set_z_index(&mut self, id: u32, z_index: i32) {
+    self.z_index.insert(id, z_index);
+    sort_by_z_index(&mut self.children, &self.z_index);
+  }
+}